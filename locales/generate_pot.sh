@@ -44,7 +44,8 @@ xgettext -o ${outfile} \
 echo >> ${outfile}
 
 custom_finder 'display_mode' \
-  show_both show_both_rev origin prefer_translation \
+  show_both show_both_rev origin prefer_translation karaoke \
+  origin_romanization show_all \
   >> ${outfile}
 
 custom_finder 'lyric_align' \
@@ -62,6 +62,33 @@ pub fn register_switch_passthrough(wind: &Window, trigger: &str) {
     bind_shortcut("win.switch-passthrough", wind, trigger);
 }
 
+pub fn register_move_unsynced_line(wind: &Window, next_trigger: &str, prev_trigger: &str) {
+    let next = SimpleAction::new("unsynced-next-line", None);
+    next.connect_activate(clone!(
+        #[weak]
+        wind,
+        move |_, _| {
+            let line = wind.imp().unsynced_line.get();
+            wind.imp().unsynced_line.set(line.saturating_add(1));
+        }
+    ));
+    wind.add_action(&next);
+
+    let prev = SimpleAction::new("unsynced-prev-line", None);
+    prev.connect_activate(clone!(
+        #[weak]
+        wind,
+        move |_, _| {
+            let line = wind.imp().unsynced_line.get();
+            wind.imp().unsynced_line.set(line.saturating_sub(1));
+        }
+    ));
+    wind.add_action(&prev);
+
+    bind_shortcut("win.unsynced-next-line", wind, next_trigger);
+    bind_shortcut("win.unsynced-prev-line", wind, prev_trigger);
+}
+
 pub fn register_set_display_mode(wind: &Window) {
     let action = SimpleAction::new("set-display-mode", Some(VariantTy::STRING));
     action.connect_activate(clone!(
@@ -119,7 +146,7 @@ fn extract_str_array(variant: &Variant) -> Option<(&str, &str)> {
     let mut iter = variant.array_iter_str().ok()?;
     let position = iter.next()?;
 
-    if !["above", "below"].contains(&position) {
+    if !["above", "romanization", "below"].contains(&position) {
         return None;
     }
 
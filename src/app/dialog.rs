@@ -1,6 +1,10 @@
 use gtk::prelude::*;
-use gtk::{ButtonsType, Window};
+use gtk::{AlertDialog, ButtonsType, Window};
 
+use crate::utils::gettext;
+
+// `MessageDialog` is deprecated since GTK 4.10
+#[allow(deprecated)]
 pub fn show_dialog(parent: Option<&impl IsA<Window>>, msg: &str, level: gtk::MessageType) {
     let msg_dialog =
         gtk::MessageDialog::new(parent, gtk::DialogFlags::MODAL, level, ButtonsType::Ok, msg);
@@ -9,3 +13,15 @@ pub fn show_dialog(parent: Option<&impl IsA<Window>>, msg: &str, level: gtk::Mes
     });
     msg_dialog.present();
 }
+
+/// asks a yes/no question, returns `true` on yes
+pub async fn ask_dialog(parent: Option<&impl IsA<Window>>, msg: &str) -> bool {
+    let alert_dialog = AlertDialog::builder()
+        .modal(true)
+        .message(msg)
+        .buttons([gettext("No"), gettext("Yes")])
+        .cancel_button(0)
+        .default_button(1)
+        .build();
+    matches!(alert_dialog.choose_future(parent).await, Ok(1))
+}
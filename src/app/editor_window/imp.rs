@@ -0,0 +1,52 @@
+use std::cell::{Cell, RefCell};
+
+use gtk::glib;
+use gtk::subclass::prelude::*;
+
+use crate::sync::lyric::edit::EditedLine;
+use crate::sync::TrackState;
+
+#[derive(Default)]
+pub struct Window {
+    pub vbox: gtk::Box,
+    pub preview_label: gtk::Label,
+
+    pub line_scrolled_window: gtk::ScrolledWindow,
+    pub line_list: gtk::ListBox,
+    /// timestamp entries of rows, to show shifted lines
+    pub time_entries: RefCell<Vec<gtk::Entry>>,
+
+    pub toolbar: gtk::Box,
+    pub shift_ms: gtk::SpinButton,
+    pub shift_following: gtk::CheckButton,
+    pub earlier_button: gtk::Button,
+    pub later_button: gtk::Button,
+    pub set_now_button: gtk::Button,
+    pub save_button: gtk::Button,
+
+    pub lines: RefCell<Vec<EditedLine>>,
+    pub use_cache: Cell<bool>,
+    /// the track whose lyric is edited, saving to others is refused
+    pub track: RefCell<TrackState>,
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for Window {
+    const NAME: &'static str = "LyricEditorWindow";
+    type Type = super::Window;
+    type ParentType = gtk::Window;
+}
+
+impl ObjectImpl for Window {
+    fn constructed(&self) {
+        self.parent_constructed();
+
+        let obj = self.obj();
+        obj.setup_ui();
+        obj.setup_callbacks();
+        obj.setup_preview();
+    }
+}
+
+impl WidgetImpl for Window {}
+impl WindowImpl for Window {}
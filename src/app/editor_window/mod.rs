@@ -0,0 +1,310 @@
+mod imp;
+
+use std::time::Duration;
+
+use glib::Object;
+use gtk::gio;
+use gtk::glib::{self, clone};
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+
+use crate::app::dialog::show_dialog;
+use crate::app::utils::lyric_position;
+use crate::log::{error, info};
+use crate::lyric_providers::utils::format_lrc_time;
+use crate::lyric_providers::{LyricLineOwned, LyricOwned};
+use crate::sync::lyric::cache::update_lyric_cache;
+use crate::sync::lyric::edit::{pair_lines, parse_timestamp, unpair_lines, EditedLine};
+use crate::sync::{
+    get_current_lyric, get_playing_track, is_track_playing, set_current_lyric, LyricState,
+    TrackState, TRACK_PLAYING_STATE,
+};
+use crate::utils::gettext;
+
+const PREVIEW_INTERVAL: Duration = Duration::from_millis(100);
+
+glib::wrapper! {
+    pub struct Window(ObjectSubclass<imp::Window>)
+        @extends gtk::Window, gtk::Widget,
+        @implements gio::ActionGroup, gio::ActionMap, gtk::Accessible, gtk::Buildable,
+                    gtk::ConstraintTarget, gtk::Native, gtk::Root, gtk::ShortcutManager;
+}
+
+impl Window {
+    pub fn new(origin: &[LyricLineOwned], translation: &[LyricLineOwned], use_cache: bool) -> Self {
+        let window: Self = Object::builder().build();
+        window.set_title(Some(&gettext("Edit lyric")));
+        window.imp().use_cache.set(use_cache);
+        window.imp().track.replace(get_playing_track());
+        window.set_lines(pair_lines(origin, translation));
+        window
+    }
+
+    fn setup_ui(&self) {
+        let imp = self.imp();
+
+        imp.vbox.set_orientation(gtk::Orientation::Vertical);
+        imp.vbox.set_spacing(6);
+
+        imp.preview_label.set_wrap(true);
+        imp.vbox.append(&imp.preview_label);
+
+        imp.line_list.set_selection_mode(gtk::SelectionMode::Single);
+        imp.line_scrolled_window.set_child(Some(&imp.line_list));
+        imp.line_scrolled_window.set_vexpand(true);
+        imp.line_scrolled_window
+            .set_hscrollbar_policy(gtk::PolicyType::Never);
+        imp.line_scrolled_window.set_height_request(400);
+        imp.line_scrolled_window.set_width_request(600);
+        imp.vbox.append(&imp.line_scrolled_window);
+
+        imp.shift_ms
+            .set_adjustment(&gtk::Adjustment::new(100., 10., 10_000., 10., 100., 0.));
+        imp.shift_ms
+            .set_tooltip_text(Some(&gettext("shift in milliseconds")));
+        imp.shift_following
+            .set_label(Some(&gettext("Shift following lines too")));
+        imp.earlier_button.set_label(&gettext("Earlier"));
+        imp.later_button.set_label(&gettext("Later"));
+        imp.set_now_button.set_label(&gettext("Set to Now"));
+        imp.set_now_button
+            .set_tooltip_text(Some(&gettext("start selected line at current position")));
+        imp.save_button.set_label(&gettext("Save"));
+
+        imp.toolbar.set_spacing(6);
+        imp.toolbar.append(&imp.shift_ms);
+        imp.toolbar.append(&imp.earlier_button);
+        imp.toolbar.append(&imp.later_button);
+        imp.toolbar.append(&imp.shift_following);
+        imp.toolbar.append(&imp.set_now_button);
+        imp.toolbar.append(&imp.save_button);
+        imp.vbox.append(&imp.toolbar);
+
+        self.set_child(Some(&imp.vbox));
+    }
+
+    fn set_lines(&self, lines: Vec<EditedLine>) {
+        let imp = self.imp();
+
+        let mut time_entries = vec![];
+        for (index, line) in lines.iter().enumerate() {
+            let time_entry = gtk::Entry::builder()
+                .text(format_lrc_time(line.line.start_time))
+                .width_chars(9)
+                .max_width_chars(9)
+                .build();
+            let text_entry = gtk::Entry::builder()
+                .text(line.line.text.as_str())
+                .hexpand(true)
+                .build();
+            let translation = line.translation.as_ref().map(|line| line.text.as_str());
+            let translation_entry = gtk::Entry::builder()
+                .text(translation.unwrap_or_default())
+                .placeholder_text(gettext("Translation"))
+                .hexpand(true)
+                .build();
+            self.connect_row(index, &time_entry, &text_entry, &translation_entry);
+
+            let row = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+            row.append(&time_entry);
+            row.append(&text_entry);
+            row.append(&translation_entry);
+            imp.line_list.append(&row);
+
+            time_entries.push(time_entry);
+        }
+
+        imp.lines.replace(lines);
+        imp.time_entries.replace(time_entries);
+    }
+
+    fn connect_row(
+        &self,
+        index: usize,
+        time_entry: &gtk::Entry,
+        text_entry: &gtk::Entry,
+        translation_entry: &gtk::Entry,
+    ) {
+        time_entry.connect_changed(clone!(
+            #[weak(rename_to = window)]
+            self,
+            move |entry| {
+                let Some(start_time) = parse_timestamp(&entry.text()) else {
+                    entry.add_css_class("error");
+                    return;
+                };
+                entry.remove_css_class("error");
+                window.imp().lines.borrow_mut()[index].set_start_time(start_time);
+            }
+        ));
+        text_entry.connect_changed(clone!(
+            #[weak(rename_to = window)]
+            self,
+            move |entry| {
+                window.imp().lines.borrow_mut()[index].set_text(&entry.text());
+            }
+        ));
+        translation_entry.connect_changed(clone!(
+            #[weak(rename_to = window)]
+            self,
+            move |entry| {
+                window.imp().lines.borrow_mut()[index].set_translation(&entry.text());
+            }
+        ));
+    }
+
+    fn setup_callbacks(&self) {
+        let imp = self.imp();
+
+        imp.earlier_button.connect_clicked(clone!(
+            #[weak(rename_to = window)]
+            self,
+            move |_| {
+                let shift_ms = window.imp().shift_ms.value() as i64;
+                window.shift_selected(-shift_ms);
+            }
+        ));
+        imp.later_button.connect_clicked(clone!(
+            #[weak(rename_to = window)]
+            self,
+            move |_| {
+                let shift_ms = window.imp().shift_ms.value() as i64;
+                window.shift_selected(shift_ms);
+            }
+        ));
+        imp.set_now_button.connect_clicked(clone!(
+            #[weak(rename_to = window)]
+            self,
+            move |_| {
+                let Some(position) = lyric_position() else {
+                    return;
+                };
+                let Some(index) = window.selected_index() else {
+                    return;
+                };
+                let start_time = window.imp().lines.borrow()[index].line.start_time;
+                let shift_ms = position.as_millis() as i64 - start_time.as_millis() as i64;
+                window.shift_selected(shift_ms);
+            }
+        ));
+        imp.save_button.connect_clicked(clone!(
+            #[weak(rename_to = window)]
+            self,
+            move |_| window.save()
+        ));
+    }
+
+    fn selected_index(&self) -> Option<usize> {
+        let row = self.imp().line_list.selected_row()?;
+        usize::try_from(row.index()).ok()
+    }
+
+    /// shifts selected line, and following ones if asked to
+    fn shift_selected(&self, shift_ms: i64) {
+        let imp = self.imp();
+        let Some(index) = self.selected_index() else {
+            return;
+        };
+
+        let start_times: Vec<_> = {
+            let mut lines = imp.lines.borrow_mut();
+            let end = if imp.shift_following.is_active() {
+                lines.len()
+            } else {
+                index + 1
+            };
+            lines[index..end]
+                .iter_mut()
+                .map(|line| {
+                    line.shift(shift_ms);
+                    line.line.start_time
+                })
+                .collect()
+        };
+        // entries write back to `lines` on change, so do not borrow it here
+        for (entry, start_time) in imp.time_entries.borrow()[index..].iter().zip(start_times) {
+            entry.set_text(&format_lrc_time(start_time));
+        }
+    }
+
+    fn setup_preview(&self) {
+        let window = self.downgrade();
+        glib::timeout_add_local(PREVIEW_INTERVAL, move || {
+            let Some(window) = window.upgrade() else {
+                return glib::ControlFlow::Break;
+            };
+            window.refresh_preview();
+            glib::ControlFlow::Continue
+        });
+    }
+
+    /// shows the edited line at current position of the player
+    fn refresh_preview(&self) {
+        let paused = TRACK_PLAYING_STATE.with_borrow(|TrackState { paused, .. }| *paused);
+        if paused {
+            return;
+        }
+        let imp = self.imp();
+        let Some(position) = lyric_position() else {
+            imp.preview_label.set_label("");
+            return;
+        };
+
+        let lines = imp.lines.borrow();
+        let text = lines
+            .iter()
+            .filter(|line| line.line.start_time <= position)
+            .max_by_key(|line| line.line.start_time)
+            .map(|EditedLine { line, translation }| match translation {
+                Some(translation) => format!("{}\n{}", line.text, translation.text),
+                None => line.text.clone(),
+            })
+            .unwrap_or_default();
+        imp.preview_label
+            .set_label(&format!("[{}] {text}", format_lrc_time(position)));
+    }
+
+    fn save(&self) {
+        let imp = self.imp();
+        let invalid_line = imp
+            .time_entries
+            .borrow()
+            .iter()
+            .position(|entry| entry.has_css_class("error"));
+        if let Some(index) = invalid_line {
+            let prompt = gettext("invalid timestamp at line ");
+            show_dialog(
+                Some(self),
+                &format!("{prompt}{}", index + 1),
+                gtk::MessageType::Error,
+            );
+            return;
+        }
+        let track = imp.track.borrow();
+        if !is_track_playing(&track) {
+            let error_msg = gettext("the playing track has changed, lyric is not saved!");
+            error!(error_msg);
+            show_dialog(Some(self), &error_msg, gtk::MessageType::Error);
+            return;
+        }
+
+        let (origin, translation) = unpair_lines(imp.lines.borrow().clone());
+        let translation = if translation.is_empty() {
+            LyricOwned::None
+        } else {
+            LyricOwned::LineTimestamp(translation)
+        };
+        info!("saved {} edited lines", origin.len());
+        set_current_lyric(LyricState {
+            origin: LyricOwned::LineTimestamp(origin),
+            translation,
+            romanization: get_current_lyric().romanization,
+        });
+
+        if imp.use_cache.get() {
+            if let Some(cache_path) = &track.cache_path {
+                update_lyric_cache(cache_path);
+            }
+        }
+    }
+}
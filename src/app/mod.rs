@@ -1,6 +1,10 @@
+pub mod editor_window;
 pub mod search_window;
+pub mod tap_sync_window;
 mod window;
 
+use std::time::Duration;
+
 use gtk::prelude::*;
 use gtk::subclass::prelude::ObjectSubclassIsExt;
 use gtk::{Application, Label};
@@ -15,13 +19,20 @@ pub mod actions;
 pub mod dialog;
 pub mod utils;
 
+#[allow(clippy::too_many_arguments)]
 pub fn build_main_window(
     app: &Application,
     enable_filter_regex: bool,
     cache_lyrics: bool,
     length_toleration_ms: u128,
+    max_lyric_stretch: Duration,
     show_default_text_on_idle: bool,
     show_lyric_on_pause: bool,
+    line_max_hold: Duration,
+    dim_ended_line: bool,
+    unsynced_lyric_mode: config::UnsyncedLyricMode,
+    context_lines_before: usize,
+    context_lines_after: usize,
     #[cfg(feature = "layer-shell")] layer_shell: bool,
     #[cfg(feature = "layer-shell")] layer_shell_anchor: crate::config::LayerShellAnchor,
 ) -> Window {
@@ -29,8 +40,14 @@ pub fn build_main_window(
         app,
         cache_lyrics,
         length_toleration_ms,
+        max_lyric_stretch,
         show_default_text_on_idle,
         show_lyric_on_pause,
+        line_max_hold,
+        dim_ended_line,
+        unsynced_lyric_mode,
+        context_lines_before,
+        context_lines_after,
     );
 
     #[cfg(feature = "layer-shell")]
@@ -58,6 +75,13 @@ pub fn build_main_window(
         .vexpand(true)
         .hexpand(false)
         .build();
+    let romanization_label = Label::builder()
+        .label("")
+        .name("romanization")
+        .vexpand(true)
+        .hexpand(false)
+        .visible(false)
+        .build();
     let below_label = Label::builder()
         .label("")
         .name("below")
@@ -66,7 +90,22 @@ pub fn build_main_window(
         .visible(false)
         .build();
 
-    for label in [&above_label, &below_label] {
+    for label in [&above_label, &romanization_label, &below_label] {
+        label.add_css_class("current");
+    }
+
+    let previous_labels: Vec<Label> = (1..=context_lines_before)
+        .map(|distance| build_context_label(&format!("previous-{distance}"), "previous"))
+        .collect();
+    let next_labels: Vec<Label> = (1..=context_lines_after)
+        .map(|distance| build_context_label(&format!("next-{distance}"), "next"))
+        .collect();
+
+    for label in [&above_label, &romanization_label, &below_label]
+        .into_iter()
+        .chain(&previous_labels)
+        .chain(&next_labels)
+    {
         utils::setup_label(label, enable_filter_regex);
     }
 
@@ -81,9 +120,25 @@ pub fn build_main_window(
         .build();
 
     verical_box.insert_child_after(&above_label, gtk::Box::NONE);
-    verical_box.insert_child_after(&below_label, Some(&above_label));
+    verical_box.insert_child_after(&romanization_label, Some(&above_label));
+    verical_box.insert_child_after(&below_label, Some(&romanization_label));
+    // the nearest previous line is the last one above current line
+    for label in &previous_labels {
+        verical_box.prepend(label);
+    }
+    for label in &next_labels {
+        verical_box.append(label);
+    }
 
     window.set_child(Some(&verical_box));
+    let _ = window.imp().lyric_labels.set(LyricLabels {
+        all: get_labels(&verical_box).expect("cannot find labels"),
+        above: above_label,
+        romanization: romanization_label,
+        below: below_label,
+        previous: previous_labels,
+        next: next_labels,
+    });
 
     let align = window.imp().lyric_align.get();
     set_lyric_align(&window, align);
@@ -99,6 +154,19 @@ pub fn build_main_window(
     window
 }
 
+fn build_context_label(name: &str, class: &str) -> Label {
+    let label = Label::builder()
+        .label("")
+        .name(name)
+        .vexpand(true)
+        .hexpand(false)
+        .visible(false)
+        .build();
+    label.add_css_class("context");
+    label.add_css_class(class);
+    label
+}
+
 pub fn set_lyric_align(window: &Window, align: config::Align) -> Option<()> {
     let vbox: gtk::Box = window.child()?.downcast().ok()?;
     vbox.set_halign(align.into());
@@ -113,19 +181,53 @@ pub fn set_lyric_align(window: &Window, align: config::Align) -> Option<()> {
     Some(())
 }
 
-fn get_labels(vbox: &gtk::Box) -> Option<[Label; 2]> {
-    let above_label: Label = vbox.first_child()?.downcast().ok()?;
-    let below_label: Label = vbox.last_child()?.downcast().ok()?;
-    Some([above_label, below_label])
+fn get_labels(vbox: &gtk::Box) -> Option<Vec<Label>> {
+    let mut labels = vec![];
+    let mut child = vbox.first_child();
+    while let Some(widget) = child {
+        child = widget.next_sibling();
+        labels.push(widget.downcast().ok()?);
+    }
+    Some(labels)
+}
+
+/// lyric labels of the main window, resolved once it is built
+pub struct LyricLabels {
+    pub above: Label,
+    pub romanization: Label,
+    pub below: Label,
+    /// context labels before current line, the nearest first
+    pub previous: Vec<Label>,
+    /// context labels after current line, the nearest first
+    pub next: Vec<Label>,
+    /// all lyric labels, from top to bottom
+    pub all: Vec<Label>,
+}
+
+pub fn lyric_labels(window: &Window) -> &LyricLabels {
+    window
+        .imp()
+        .lyric_labels
+        .get()
+        .expect("initialization failed!")
+}
+
+/// all lyric labels, from top to bottom
+pub fn get_lyric_labels(window: &Window) -> Vec<Label> {
+    lyric_labels(window).all.clone()
 }
 
 pub fn get_label(window: &Window, position: &str) -> Label {
-    let Some(vbox) = window.child().and_then(|c| c.downcast::<gtk::Box>().ok()) else {
-        panic!("initialization failed!")
-    };
-    get_labels(&vbox)
-        .expect("cannot find labels")
-        .into_iter()
-        .find(|label| label.widget_name() == position)
-        .unwrap()
+    let labels = lyric_labels(window);
+    match position {
+        "above" => labels.above.clone(),
+        "romanization" => labels.romanization.clone(),
+        "below" => labels.below.clone(),
+        _ => labels
+            .all
+            .iter()
+            .find(|label| label.widget_name() == position)
+            .cloned()
+            .unwrap(),
+    }
 }
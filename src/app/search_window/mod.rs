@@ -12,9 +12,10 @@ use gtk::subclass::prelude::*;
 use gtk::{gio, glib, ColumnViewColumn, ListItem};
 use tokio::task::JoinSet;
 
-use crate::{glib_spawn, tokio_spawn, LYRIC_PROVIDERS};
+use crate::{glib_spawn, tokio_spawn, LYRIC_PROVIDERS, MAIN_WINDOW};
 
 use crate::app::dialog::show_dialog;
+use crate::sync::actions::utils::set_lyric_offset;
 use crate::sync::lyric::cache::update_lyric_cache;
 use crate::sync::{fuzzy_match_song, get_lyric_cache_path, set_current_lyric, LyricState};
 
@@ -188,7 +189,7 @@ impl Window {
                 set.spawn(async move {
                     let artists = artists.iter().map(|a| &**a).collect::<Vec<&str>>();
                     let result = provider
-                        .search_song_detailed(&album, &artists, &title)
+                        .search_song_detailed(&album, &artists, &title, None)
                         .await;
                     (result, provider_id, idx, title)
                 });
@@ -365,9 +366,22 @@ impl Window {
                         Ok(lyric) => {
                             let origin = provider.parse_lyric(&lyric);
                             let translation = provider.parse_translated_lyric(&lyric);
+                            let romanization = provider.parse_romanized_lyric(&lyric);
+                            let metadata = provider.parse_lyric_metadata(&lyric);
                             set_current_lyric(LyricState {
                                 origin,
                                 translation,
+                                romanization,
+                            });
+                            // fitted to the length of the song found before
+                            MAIN_WINDOW.with_borrow(|main_window| {
+                                if let Some(main_window) = main_window {
+                                    main_window.imp().lyric_stretch.set(None);
+                                    // keep the offset set by user, unless the lyric file has one
+                                    if metadata.offset_ms != 0 {
+                                        set_lyric_offset(main_window, metadata.lyric_offset_ms());
+                                    }
+                                }
                             });
 
                             if window.imp().use_cache.get() {
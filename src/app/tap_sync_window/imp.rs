@@ -0,0 +1,52 @@
+use std::cell::{Cell, RefCell};
+
+use gtk::glib;
+use gtk::subclass::prelude::*;
+
+use crate::sync::lyric::tap::TapSync;
+use crate::sync::TrackState;
+
+#[derive(Default)]
+pub struct Window {
+    pub stack: gtk::Stack,
+
+    // page to paste plain text
+    pub text_page: gtk::Box,
+    pub text_scrolled_window: gtk::ScrolledWindow,
+    pub text_view: gtk::TextView,
+    pub start_button: gtk::Button,
+
+    // page to tap on
+    pub tap_page: gtk::Box,
+    pub progress_label: gtk::Label,
+    pub current_label: gtk::Label,
+    pub next_label: gtk::Label,
+    pub tap_button: gtk::Button,
+    pub undo_button: gtk::Button,
+    pub finish_button: gtk::Button,
+
+    pub tap_sync: RefCell<TapSync>,
+    pub use_cache: Cell<bool>,
+    /// the track whose lyric is tapped, saving to others is refused
+    pub track: RefCell<TrackState>,
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for Window {
+    const NAME: &'static str = "TapSyncWindow";
+    type Type = super::Window;
+    type ParentType = gtk::Window;
+}
+
+impl ObjectImpl for Window {
+    fn constructed(&self) {
+        self.parent_constructed();
+
+        let obj = self.obj();
+        obj.setup_ui();
+        obj.setup_callbacks();
+    }
+}
+
+impl WidgetImpl for Window {}
+impl WindowImpl for Window {}
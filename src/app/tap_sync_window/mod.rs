@@ -0,0 +1,280 @@
+mod imp;
+
+use glib::Object;
+use gtk::gdk::Key;
+use gtk::gio;
+use gtk::glib::{self, clone};
+use gtk::prelude::*;
+use gtk::subclass::prelude::*;
+
+use crate::app::dialog::{ask_dialog, show_dialog};
+use crate::app::utils::lyric_position;
+use crate::log::{error, info, warn};
+use crate::lyric_providers::{LyricLineOwned, LyricOwned};
+use crate::sync::lyric::cache::update_lyric_cache;
+use crate::sync::lyric::tap::{retime_paired_lines, TapSync};
+use crate::sync::{
+    get_current_lyric, get_playing_track, is_track_playing, set_current_lyric, LyricState,
+    TrackState, TRACK_PLAYING_STATE,
+};
+use crate::utils::gettext;
+
+const TEXT_PAGE: &str = "text";
+const TAP_PAGE: &str = "tap";
+
+glib::wrapper! {
+    pub struct Window(ObjectSubclass<imp::Window>)
+        @extends gtk::Window, gtk::Widget,
+        @implements gio::ActionGroup, gio::ActionMap, gtk::Accessible, gtk::Buildable,
+                    gtk::ConstraintTarget, gtk::Native, gtk::Root, gtk::ShortcutManager;
+}
+
+impl Window {
+    pub fn new(text: &str, use_cache: bool) -> Self {
+        let window: Self = Object::builder().build();
+        window.set_title(Some(&gettext("Tap to sync lyric")));
+        window.imp().text_view.buffer().set_text(text);
+        window.imp().use_cache.set(use_cache);
+        window.imp().track.replace(get_playing_track());
+        window
+    }
+
+    fn setup_ui(&self) {
+        let imp = self.imp();
+
+        imp.text_page.set_orientation(gtk::Orientation::Vertical);
+        imp.text_page.set_spacing(6);
+        imp.text_view.set_wrap_mode(gtk::WrapMode::WordChar);
+        imp.text_scrolled_window.set_child(Some(&imp.text_view));
+        imp.text_scrolled_window.set_vexpand(true);
+        imp.text_scrolled_window.set_height_request(400);
+        imp.text_scrolled_window.set_width_request(400);
+        imp.start_button.set_label(&gettext("Start Tapping"));
+        imp.start_button.set_tooltip_text(Some(&gettext(
+            "play the track, then press Space as each line starts",
+        )));
+        imp.text_page.append(&imp.text_scrolled_window);
+        imp.text_page.append(&imp.start_button);
+
+        imp.tap_page.set_orientation(gtk::Orientation::Vertical);
+        imp.tap_page.set_spacing(12);
+        imp.current_label.set_wrap(true);
+        imp.current_label.add_css_class("title-2");
+        imp.next_label.set_wrap(true);
+        imp.next_label.add_css_class("dim-label");
+        imp.tap_button.set_label(&gettext("Tap"));
+        imp.tap_button.set_tooltip_text(Some("Space"));
+        imp.undo_button.set_label(&gettext("Undo"));
+        imp.undo_button.set_tooltip_text(Some("Backspace"));
+        imp.finish_button.set_label(&gettext("Finish"));
+
+        let buttons = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+        buttons.set_halign(gtk::Align::Center);
+        buttons.append(&imp.tap_button);
+        buttons.append(&imp.undo_button);
+        buttons.append(&imp.finish_button);
+
+        imp.tap_page.append(&imp.progress_label);
+        imp.tap_page.append(&imp.current_label);
+        imp.tap_page.append(&imp.next_label);
+        imp.tap_page.append(&buttons);
+
+        imp.stack.add_named(&imp.text_page, Some(TEXT_PAGE));
+        imp.stack.add_named(&imp.tap_page, Some(TAP_PAGE));
+        self.set_child(Some(&imp.stack));
+    }
+
+    fn setup_callbacks(&self) {
+        let imp = self.imp();
+
+        imp.start_button.connect_clicked(clone!(
+            #[weak(rename_to = window)]
+            self,
+            move |_| window.start()
+        ));
+        imp.tap_button.connect_clicked(clone!(
+            #[weak(rename_to = window)]
+            self,
+            move |_| window.tap()
+        ));
+        imp.undo_button.connect_clicked(clone!(
+            #[weak(rename_to = window)]
+            self,
+            move |_| window.undo()
+        ));
+        imp.finish_button.connect_clicked(clone!(
+            #[weak(rename_to = window)]
+            self,
+            move |_| window.finish()
+        ));
+
+        // capture keys before focused buttons activate on them
+        let keys = gtk::EventControllerKey::new();
+        keys.set_propagation_phase(gtk::PropagationPhase::Capture);
+        let window = self.downgrade();
+        keys.connect_key_pressed(move |_, key, _, _| {
+            let Some(window) = window.upgrade() else {
+                return glib::Propagation::Proceed;
+            };
+            if window.imp().stack.visible_child_name().as_deref() != Some(TAP_PAGE) {
+                return glib::Propagation::Proceed;
+            }
+            match key {
+                Key::space | Key::Return | Key::KP_Enter => window.tap(),
+                Key::BackSpace => window.undo(),
+                _ => return glib::Propagation::Proceed,
+            }
+            glib::Propagation::Stop
+        });
+        self.add_controller(keys);
+    }
+
+    fn start(&self) {
+        let imp = self.imp();
+        let buffer = imp.text_view.buffer();
+        let text = buffer.text(&buffer.start_iter(), &buffer.end_iter(), false);
+
+        let tap_sync = TapSync::new(&text);
+        if tap_sync.is_empty() {
+            show_dialog(
+                Some(self),
+                &gettext("no lyric text to sync!"),
+                gtk::MessageType::Error,
+            );
+            return;
+        }
+        imp.tap_sync.replace(tap_sync);
+        imp.stack.set_visible_child_name(TAP_PAGE);
+        self.refresh_labels();
+    }
+
+    fn tap(&self) {
+        let paused = TRACK_PLAYING_STATE.with_borrow(|TrackState { paused, .. }| *paused);
+        let position = lyric_position().filter(|_| !paused);
+        let Some(position) = position else {
+            warn!("ignored tap, as no track is playing");
+            return;
+        };
+        let mut tap_sync = self.imp().tap_sync.borrow_mut();
+        if tap_sync.is_finished() {
+            return;
+        }
+        let tapped = tap_sync.tap(position);
+        drop(tap_sync);
+
+        if tapped {
+            self.refresh_labels();
+        } else {
+            warn!("ignored tap at {position:?}, before the last one");
+            show_dialog(
+                Some(self),
+                &gettext("the track was seeked back, undo taps after this position first!"),
+                gtk::MessageType::Warning,
+            );
+        }
+    }
+
+    fn undo(&self) {
+        self.imp().tap_sync.borrow_mut().undo();
+        self.refresh_labels();
+    }
+
+    fn refresh_labels(&self) {
+        let imp = self.imp();
+        let tap_sync = imp.tap_sync.borrow();
+
+        imp.progress_label
+            .set_label(&format!("{}/{}", tap_sync.tapped(), tap_sync.len()));
+        imp.current_label
+            .set_label(tap_sync.current_line().unwrap_or_default());
+        imp.next_label
+            .set_label(tap_sync.next_line().unwrap_or_default());
+        imp.tap_button.set_sensitive(!tap_sync.is_finished());
+        imp.undo_button.set_sensitive(tap_sync.tapped() != 0);
+    }
+
+    /// sets tapped lines as current lyric, along with retimed translation
+    fn finish(&self) {
+        let imp = self.imp();
+        let tap_sync = imp.tap_sync.borrow().clone();
+        info!("tapped {} of {} lines", tap_sync.tapped(), tap_sync.len());
+
+        let origin = tap_sync.into_lyric();
+        let LyricOwned::LineTimestamp(lines) = &origin else {
+            self.close();
+            return;
+        };
+        if !self.is_track_kept() {
+            return;
+        }
+
+        let current = get_current_lyric();
+        let old_lines = match &current.origin {
+            LyricOwned::LineTimestamp(old_lines) => old_lines.as_slice(),
+            _ => &[],
+        };
+        let retime = |lyric: &LyricOwned| match lyric {
+            LyricOwned::LineTimestamp(paired) => {
+                retime_paired_lines(old_lines, paired, lines).map(line_timestamp)
+            }
+            _ => Some(LyricOwned::None),
+        };
+
+        if let (Some(translation), Some(romanization)) =
+            (retime(&current.translation), retime(&current.romanization))
+        {
+            self.save(origin, translation, romanization);
+            return;
+        }
+        glib::spawn_future_local(clone!(
+            #[weak(rename_to = window)]
+            self,
+            async move {
+                let msg = gettext(
+                    "lyric text has changed, its translation cannot be kept. Save without it?",
+                );
+                if ask_dialog(Some(&window), &msg).await {
+                    window.save(origin, LyricOwned::None, LyricOwned::None);
+                }
+            }
+        ));
+    }
+
+    /// whether the track tapped for is still playing, shows an error if not
+    fn is_track_kept(&self) -> bool {
+        let kept = is_track_playing(&self.imp().track.borrow());
+        if !kept {
+            let error_msg = gettext("the playing track has changed, lyric is not saved!");
+            error!(error_msg);
+            show_dialog(Some(self), &error_msg, gtk::MessageType::Error);
+        }
+        kept
+    }
+
+    fn save(&self, origin: LyricOwned, translation: LyricOwned, romanization: LyricOwned) {
+        // the track may change while asking
+        if !self.is_track_kept() {
+            return;
+        }
+        let imp = self.imp();
+        set_current_lyric(LyricState {
+            origin,
+            translation,
+            romanization,
+        });
+        if imp.use_cache.get() {
+            if let Some(cache_path) = &imp.track.borrow().cache_path {
+                update_lyric_cache(cache_path);
+            }
+        }
+        self.close();
+    }
+}
+
+fn line_timestamp(lines: Vec<LyricLineOwned>) -> LyricOwned {
+    if lines.is_empty() {
+        LyricOwned::None
+    } else {
+        LyricOwned::LineTimestamp(lines)
+    }
+}
@@ -1,8 +1,10 @@
 use std::path::Path;
+use std::time::Duration;
 
-use crate::EXCLUDED_REGEXES;
+use crate::{EXCLUDED_REGEXES, MAIN_WINDOW};
 
 use gtk::prelude::*;
+use gtk::subclass::prelude::ObjectSubclassIsExt;
 use gtk::Label;
 
 use super::window;
@@ -126,8 +128,9 @@ pub fn setup_label(label: &Label, hide_filtered_words: bool) {
     label.set_wrap_mode(gtk::pango::WrapMode::Word);
 
     if hide_filtered_words {
+        // markup of karaoke mode is not matched against
         label.connect_label_notify(|label| {
-            let text = label.label();
+            let text = label.text();
             let visible = !has_filtered_word(&text) && !text.is_empty();
             label.set_visible(visible);
         });
@@ -138,3 +141,8 @@ pub fn setup_label(label: &Label, hide_filtered_words: bool) {
         });
     }
 }
+
+/// position of the playing track, with lyric offset and time-stretch applied
+pub fn lyric_position() -> Option<Duration> {
+    MAIN_WINDOW.with_borrow(|window| window.as_ref()?.lyric_time())
+}
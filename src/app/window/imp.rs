@@ -1,5 +1,5 @@
-use std::cell::Cell;
-use std::time::SystemTime;
+use std::cell::{Cell, OnceCell, RefCell};
+use std::time::{Duration, SystemTime};
 
 use crate::utils::gettext;
 use gio::Settings;
@@ -12,7 +12,8 @@ use gtk::{gio, glib, ApplicationWindow, PopoverMenu};
 use std::sync::OnceLock;
 
 use crate::app::utils::set_click_pass_through;
-use crate::config::{Align, LyricDisplayMode};
+use crate::config::{Align, LyricDisplayMode, UnsyncedLyricMode};
+use crate::sync::lyric::stretch::{SyncPoint, TimeStretch};
 use crate::sync::{OsImp, OS};
 
 #[derive(Default)]
@@ -26,13 +27,30 @@ pub struct Window {
     pub lyric_display_mode: Cell<LyricDisplayMode>,
     pub show_default_text_on_idle: Cell<bool>,
     pub show_lyric_on_pause: Cell<bool>,
+    pub line_max_hold: Cell<Duration>,
+    pub dim_ended_line: Cell<bool>,
+    pub context_lines_before: Cell<usize>,
+    pub context_lines_after: Cell<usize>,
+    /// index of the line shown as current, to animate line changes
+    pub current_line: Cell<Option<usize>>,
+    pub unsynced_lyric_mode: Cell<UnsyncedLyricMode>,
+    /// first line of unsynced lyric to show, in manual mode
+    pub unsynced_line: Cell<usize>,
 
     pub lyric_start: Cell<Option<SystemTime>>,
     pub lyric_offset_ms: Cell<i64>,
+    pub lyric_stretch: Cell<Option<TimeStretch>>,
+    /// last two lines synced with `sync-now` actions, to solve the stretch from
+    pub sync_points: RefCell<Vec<SyncPoint>>,
     pub length_toleration_ms: Cell<u128>,
+    pub max_lyric_stretch: Cell<Duration>,
 
     // widgets
+    pub lyric_labels: OnceCell<crate::app::LyricLabels>,
     pub headerbar: gtk::HeaderBar,
+    /// never shown, only used to read karaoke colors from theme,
+    /// it lives in the headerbar so that theme selectors on the window apply
+    pub karaoke_probe: gtk::Label,
     pub menubutton: gtk::MenuButton,
     pub menu: gio::Menu,
     pub player_menu: gio::Menu,
@@ -60,6 +78,10 @@ impl ObjectImpl for Window {
         obj.set_titlebar(Some(&self.headerbar));
         obj.load_window_state();
 
+        self.karaoke_probe.add_css_class("karaoke-sung");
+        self.karaoke_probe.set_visible(false);
+        self.headerbar.pack_start(&self.karaoke_probe);
+
         self.headerbar.set_decoration_layout(Some("menu:close"));
         self.menubutton.set_icon_name("open-menu-symbolic");
 
@@ -73,6 +95,11 @@ impl ObjectImpl for Window {
         );
         let reload_theme = MenuItem::new(Some(&gettext("Reload theme")), Some("app.reload-theme"));
         let search_lyric = MenuItem::new(Some(&gettext("Search lyric")), Some("app.search-lyric"));
+        let edit_lyric = MenuItem::new(Some(&gettext("Edit lyric")), Some("app.edit-lyric"));
+        let tap_sync_lyric = MenuItem::new(
+            Some(&gettext("Tap to sync lyric")),
+            Some("app.tap-sync-lyric"),
+        );
         let refetch_lyric =
             MenuItem::new(Some(&gettext("Refetch lyric")), Some("app.refetch-lyric"));
         let remove_lyric = MenuItem::new(
@@ -132,10 +159,57 @@ impl ObjectImpl for Window {
                 Some(&gettext("Translated Lyric")),
                 Some("app.export-lyric(false)"),
             );
+            self.export_lyric_menu.append(
+                Some(&gettext("Bilingual Lyric")),
+                Some("app.export-bilingual-lyric(false)"),
+            );
+            self.export_lyric_menu.append(
+                Some(&gettext("Bilingual Lyric (Same Line)")),
+                Some("app.export-bilingual-lyric(true)"),
+            );
+            self.export_lyric_menu
+                .append(Some(&gettext("Embed into File")), Some("app.embed-lyric"));
             play_section.append_submenu(Some(&gettext("Export Lyric")), &self.export_lyric_menu);
         }
 
-        for item in [&search_lyric, &remove_lyric, &refetch_lyric] {
+        let lyric_offset_menu = gio::Menu::default();
+        lyric_offset_menu.append(
+            Some(&gettext("Delay 100ms")),
+            Some("app.lyric-offset-increase"),
+        );
+        lyric_offset_menu.append(
+            Some(&gettext("Advance 100ms")),
+            Some("app.lyric-offset-decrease"),
+        );
+        lyric_offset_menu.append(
+            Some(&gettext("Reset Offset")),
+            Some("app.lyric-offset-reset"),
+        );
+        lyric_offset_menu.append(
+            Some(&gettext("Current Line Starts Now")),
+            Some("app.sync-now-current-line"),
+        );
+        lyric_offset_menu.append(
+            Some(&gettext("Next Line Starts Now")),
+            Some("app.sync-now-next-line"),
+        );
+        lyric_offset_menu.append(
+            Some(&gettext("Stretch by Last Two Synced Lines")),
+            Some("app.lyric-stretch-by-sync"),
+        );
+        lyric_offset_menu.append(
+            Some(&gettext("Reset Stretch")),
+            Some("app.lyric-stretch-reset"),
+        );
+        play_section.append_submenu(Some(&gettext("Lyric Offset")), &lyric_offset_menu);
+
+        for item in [
+            &search_lyric,
+            &edit_lyric,
+            &tap_sync_lyric,
+            &remove_lyric,
+            &refetch_lyric,
+        ] {
             play_section.append_item(item);
         }
 
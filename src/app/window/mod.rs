@@ -1,12 +1,14 @@
 mod imp;
 
+use std::time::Duration;
+
 use gio::Settings;
 use glib::Object;
 use gtk::prelude::*;
 use gtk::subclass::prelude::*;
 use gtk::{gio, glib, Application};
 
-use crate::config::{Align, LyricDisplayMode};
+use crate::config::{Align, LyricDisplayMode, UnsyncedLyricMode};
 use crate::APP_ID_FIXED;
 
 glib::wrapper! {
@@ -17,12 +19,19 @@ glib::wrapper! {
 }
 
 impl Window {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         app: &Application,
         cache_lyrics: bool,
         length_toleration_ms: u128,
+        max_lyric_stretch: Duration,
         show_default_text_on_idle: bool,
         show_lyric_on_pause: bool,
+        line_max_hold: Duration,
+        dim_ended_line: bool,
+        unsynced_lyric_mode: UnsyncedLyricMode,
+        context_lines_before: usize,
+        context_lines_after: usize,
     ) -> Self {
         let window: Self = Object::builder().property("application", app).build();
         let imp = window.imp();
@@ -31,12 +40,27 @@ impl Window {
 
         imp.cache_lyrics.set(cache_lyrics);
         imp.length_toleration_ms.set(length_toleration_ms);
+        imp.max_lyric_stretch.set(max_lyric_stretch);
         imp.show_default_text_on_idle.set(show_default_text_on_idle);
         imp.show_lyric_on_pause.set(show_lyric_on_pause);
+        imp.line_max_hold.set(line_max_hold);
+        imp.dim_ended_line.set(dim_ended_line);
+        imp.unsynced_lyric_mode.set(unsynced_lyric_mode);
+        imp.context_lines_before.set(context_lines_before);
+        imp.context_lines_after.set(context_lines_after);
 
         window
     }
 
+    /// lyric time of the playing track, with lyric offset and time-stretch applied
+    pub fn lyric_time(&self) -> Option<Duration> {
+        let elapsed = self.imp().lyric_start.get()?.elapsed().ok()?;
+        Some(match self.imp().lyric_stretch.get() {
+            Some(stretch) => stretch.lyric_time(elapsed),
+            None => elapsed,
+        })
+    }
+
     fn setup_settings(&self) {
         let settings = Settings::new(APP_ID_FIXED);
         self.imp()
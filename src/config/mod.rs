@@ -2,6 +2,9 @@ use documented::DocumentedFields;
 use serde::{Deserialize, Serialize};
 use strum::{EnumIter, EnumString};
 
+use crate::lyric_providers::command::CommandProviderConfig;
+use crate::lyric_providers::http::HttpProviderConfig;
+use crate::lyric_providers::library::LyricLibraryConfig;
 use crate::lyric_providers::netease::Netease;
 use crate::lyric_providers::qqmusic::QQMusicConfig;
 use crate::lyric_providers::LyricProvider;
@@ -35,6 +38,23 @@ pub enum LyricDisplayMode {
     ShowBothRev,
     Origin,
     PreferTranslation,
+    /// fills original lyric as it is sung, with translation below
+    Karaoke,
+    /// original lyric with romanization below
+    OriginRomanization,
+    /// original lyric, romanization, then translation
+    ShowAll,
+}
+
+#[derive(Clone, Copy, Default, EnumIter, strum::Display, EnumString, Serialize, Deserialize)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum UnsyncedLyricMode {
+    /// scroll through lyric at a pace estimated from track length
+    #[default]
+    Scroll,
+    /// page through lyric with shortcuts
+    Manual,
 }
 
 #[derive(Deserialize, Serialize, DocumentedFields)]
@@ -56,6 +76,13 @@ pub struct Config {
     /// waylyrics set it's weight as zero, mark it a best choice
     pub length_toleration: String,
 
+    /// stretch fetched lyric linearly to the length of the track,
+    /// if the song found by lyric provider is longer or shorter no more than this
+    ///
+    /// for lyric of a different master or edit, which drifts gradually.
+    /// set to "0s" to disable
+    pub max_lyric_stretch: String,
+
     /// whether to cache lyrics
     ///
     /// note: persistenced lyric offset depends on this
@@ -79,12 +106,26 @@ pub struct Config {
     /// it will try to extract translated lyric
     pub extract_translated_lyric: bool,
 
+    /// encodings to try in order, for local lyric files not in UTF-8 and without BOM,
+    /// like `["gbk", "big5", "shift_jis"]`
+    ///
+    /// waylyrics guesses the encoding if none of them fits
+    pub lyric_encodings: Vec<String>,
+
+    /// put between original and translated text,
+    /// when exporting bilingual lyric in the same line
+    #[cfg(feature = "export-lyric")]
+    pub bilingual_lrc_separator: String,
+
     /// hide lyric if it matches any of these regexies
     ///
     /// inspired by LyricX's filter list
     pub filter_regexies: Vec<String>,
 
-    /// available options: 网易云音乐, QQ音乐, LRCLib
+    /// ids of lyric providers to search, in order
+    ///
+    /// available options: netease (网易云音乐), qqmusic (QQ音乐), lrclib (LRCLib),
+    /// kugou (酷狗音乐), library (lyric files in `lyric-library`)
     pub lyric_search_source: Vec<String>,
 
     /// skip auto search on new track
@@ -98,6 +139,30 @@ pub struct Config {
     /// if enabled, waylyrics will show lyric on `Pause`
     pub show_lyric_on_pause: bool,
 
+    /// hide a lyric line after it has been shown for this long,
+    /// if the lyric does not tell when the line ends
+    ///
+    /// set to "0s" to keep it until the next line starts
+    pub line_max_hold: String,
+
+    /// if enabled, ended lines are dimmed (css class `ended`) instead of hidden
+    pub dim_ended_line: bool,
+
+    /// how many lines before current line to show
+    ///
+    /// these labels have css class `context` and `previous`
+    pub context_lines_before: usize,
+
+    /// how many lines after current line to show
+    ///
+    /// these labels have css class `context` and `next`
+    pub context_lines_after: usize,
+
+    /// how to show lyrics without timestamps -- scroll or manual
+    ///
+    /// in manual mode, use `unsynced-next-line` and `unsynced-prev-line` triggers
+    pub unsynced_lyric_mode: UnsyncedLyricMode,
+
     /// whether to run tray-icon service
     #[cfg(feature = "tray-icon")]
     pub show_tray_icon: bool,
@@ -139,6 +204,37 @@ pub struct Config {
     /// waylyrics will set cookies for the QQMusicApi service at startup.
     pub qqmusic: QQMusicConfig,
 
+    /// folders of lyric files for `library` in `lyric-search-source`
+    ///
+    /// `.lrc`, `.ttml`, `.srt` and `.vtt` files in `dirs` and their subfolders are indexed
+    /// by `[ti:]`, `[ar:]`, `[al:]` and `[length:]` tags of LRC,
+    /// or by the first of `filename-patterns` matching the file name without extension,
+    /// with `{title}`, `{artist}` and `{album}`.
+    /// files added later are found after restart
+    pub lyric_library: LyricLibraryConfig,
+
+    /// lyric providers over HTTP, each one as `[[http-providers]]`,
+    /// to be enabled by `id` in `lyric-search-source`
+    ///
+    /// songs are searched by `search-url`, with `{title}`, `{artist}`, `{album}`
+    /// and `{duration}` in seconds, and `results-path` tells where the result list is.
+    /// `id-path`, `title-path`, `artist-path`, `album-path` and `length-path`
+    /// are paths in each result, like `album.name`,
+    /// `length-unit` is either "seconds" or "milliseconds".
+    ///
+    /// lyric is fetched by `lyric-url` with `{id}`,
+    /// and picked by `lrc-path` and `translation-path`.
+    /// `headers` are sent with every request
+    pub http_providers: Vec<HttpProviderConfig>,
+
+    /// lyric providers running an executable, each one as `[[command-providers]]`,
+    /// to be enabled by `id` in `lyric-search-source`
+    ///
+    /// `command` runs with `args` for each request, which is one line of JSON in stdin,
+    /// and is killed if it does not exit in `timeout`.
+    /// see `src/lyric_providers/command/mod.rs` for the protocol
+    pub command_providers: Vec<CommandProviderConfig>,
+
     /// Color scheme used for Gtk interface -- light, dark or auto (use system)
     ///
     /// Not supported on Windows.
@@ -162,6 +258,20 @@ pub struct Triggers {
     pub refetch_lyric: String,
     /// whether to allow mouse click-through
     pub switch_passthrough: String,
+    /// show next line of unsynced lyric, in manual mode
+    pub unsynced_next_line: String,
+    /// show previous line of unsynced lyric, in manual mode
+    pub unsynced_prev_line: String,
+    /// delay lyric by 100ms, saved to lyric cache
+    pub lyric_offset_increase: String,
+    /// show lyric 100ms sooner, saved to lyric cache
+    pub lyric_offset_decrease: String,
+    /// reset lyric offset to zero
+    pub lyric_offset_reset: String,
+    /// press as the shown line starts to be sung, to calibrate lyric offset
+    pub sync_now_current_line: String,
+    /// press as the line after the shown one starts to be sung, to calibrate lyric offset
+    pub sync_now_next_line: String,
 }
 
 impl Default for Triggers {
@@ -172,6 +282,13 @@ impl Default for Triggers {
             search_lyric: "<Control>s".into(),
             refetch_lyric: "<Alt><Shift>l".into(),
             switch_passthrough: "<Alt>p".into(),
+            unsynced_next_line: "<Alt>Down".into(),
+            unsynced_prev_line: "<Alt>Up".into(),
+            lyric_offset_increase: "<Alt>equal".into(),
+            lyric_offset_decrease: "<Alt>minus".into(),
+            lyric_offset_reset: "<Alt>0".into(),
+            sync_now_current_line: "<Alt>s".into(),
+            sync_now_next_line: "<Alt><Shift>s".into(),
         }
     }
 }
@@ -183,15 +300,21 @@ impl Default for Config {
             player_sync_interval: "2s".to_owned(),
             lyric_update_interval: "20ms".to_owned(),
             length_toleration: "2s".to_owned(),
+            max_lyric_stretch: "0s".to_owned(),
             theme: "default".into(),
             cache_lyrics: true,
             enable_filter_regex: false,
             enable_local_lyric: true,
             filter_regexies: default_filter_regexies(),
-            lyric_search_source: vec![Netease.unique_name().into()],
+            lyric_search_source: vec![Netease.id().into()],
             skip_auto_search: false,
             show_default_text_on_idle: true,
             show_lyric_on_pause: true,
+            line_max_hold: "20s".into(),
+            dim_ended_line: false,
+            context_lines_before: 0,
+            context_lines_after: 0,
+            unsynced_lyric_mode: UnsyncedLyricMode::default(),
             #[cfg(feature = "tray-icon")]
             show_tray_icon: true,
             #[cfg(feature = "layer-shell")]
@@ -201,8 +324,14 @@ impl Default for Config {
             player_name_blacklist: vec!["firefox".into()],
             player_identity_blacklist: vec![],
             extract_translated_lyric: true,
+            lyric_encodings: vec![],
+            #[cfg(feature = "export-lyric")]
+            bilingual_lrc_separator: " / ".into(),
             triggers: Triggers::default(),
             qqmusic: QQMusicConfig::default(),
+            lyric_library: LyricLibraryConfig::default(),
+            http_providers: vec![],
+            command_providers: vec![],
             color_scheme: ColorScheme::default(),
             theme_dark_switch: false,
         }
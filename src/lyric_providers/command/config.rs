@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+/// a lyric provider running an executable for each request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CommandProviderConfig {
+    /// ASCII id to put in `lyric-search-source`
+    pub id: String,
+    /// display name, same as `id` if empty
+    #[serde(default)]
+    pub name: String,
+
+    /// path of the executable, or its name in `PATH`
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// the command is killed if it does not exit in time, like "10s"
+    #[serde(default = "default_timeout")]
+    pub timeout: String,
+}
+
+fn default_timeout() -> String {
+    "10s".into()
+}
+
+impl Default for CommandProviderConfig {
+    fn default() -> Self {
+        Self {
+            id: String::new(),
+            name: String::new(),
+            command: String::new(),
+            args: vec![],
+            timeout: default_timeout(),
+        }
+    }
+}
@@ -0,0 +1,268 @@
+//! lyric provider defined in config, running an executable for each request
+//!
+//! a request is written to stdin of the command as one line of JSON:
+//!
+//! ```json
+//! {"action": "search", "title": "Hello", "artists": ["A", "B"], "album": "", "length": 200.5}
+//! {"action": "search-keyword", "keyword": "Hello A"}
+//! {"action": "query", "id": "42"}
+//! ```
+//!
+//! `length` is in seconds, or `null` if unknown.
+//! searches are answered in stdout with a list of songs,
+//! in which `singer`, `album` and `length` in seconds are optional:
+//!
+//! ```json
+//! [{"id": "42", "title": "Hello", "singer": "A, B", "album": "Album", "length": 200.5}]
+//! ```
+//!
+//! queries are answered with LRC or plain text lyric,
+//! or with `{"lyric": "...", "translation": "..."}` to give translation too.
+//! the command tells failure by non-zero exit status, with the reason in stderr.
+
+use std::process::{ExitStatus, Stdio};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use crate::log::debug;
+use crate::tokio_spawn;
+use crate::utils::{parse_time, ParseError};
+
+use super::utils::{leak_provider_names, lrc_or_none};
+use super::{Capabilities, LyricOwned, LyricStore, SongInfo};
+
+mod config;
+pub use config::CommandProviderConfig;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("invalid timeout of lyric provider {id}: {source}")]
+    InvalidTimeout { id: String, source: ParseError },
+    #[error("cannot run lyric command: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("lyric command did not exit in {0:?}")]
+    Timeout(Duration),
+    #[error("lyric command exited with {status}: {stderr}")]
+    Failed { status: ExitStatus, stderr: String },
+    #[error("output of lyric command is not in UTF-8")]
+    NotUtf8,
+    #[error("malformed song list from lyric command: {0}")]
+    MalformedSongs(serde_json::Error),
+}
+
+/// written to stdin of the command
+#[derive(Debug, Serialize)]
+#[serde(tag = "action", rename_all = "kebab-case")]
+pub enum CommandRequest<'a> {
+    Search {
+        title: &'a str,
+        artists: &'a [&'a str],
+        album: &'a str,
+        /// in seconds
+        length: Option<f64>,
+    },
+    SearchKeyword {
+        keyword: &'a str,
+    },
+    Query {
+        id: &'a str,
+    },
+}
+
+#[derive(Deserialize)]
+struct CommandSong {
+    id: String,
+    title: String,
+    #[serde(default)]
+    singer: String,
+    album: Option<String>,
+    /// in seconds
+    length: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct CommandLyric {
+    lyric: Option<String>,
+    translation: Option<String>,
+}
+
+pub struct CommandProvider {
+    id: &'static str,
+    name: &'static str,
+    timeout: Duration,
+    config: Arc<CommandProviderConfig>,
+}
+
+impl CommandProvider {
+    pub fn new(config: CommandProviderConfig) -> Result<Self, Error> {
+        let timeout = parse_time(&config.timeout).map_err(|source| Error::InvalidTimeout {
+            id: config.id.clone(),
+            source,
+        })?;
+        let (id, name) = leak_provider_names(&config.id, &config.name);
+        Ok(Self {
+            id,
+            name,
+            timeout,
+            config: Arc::new(config),
+        })
+    }
+
+    async fn request(&self, request: &CommandRequest<'_>) -> Result<String> {
+        let request = serde_json::to_string(request)?;
+        let config = self.config.clone();
+        let timeout = self.timeout;
+
+        Ok(tokio_spawn!(async move { run_command(&config, &request, timeout).await }).await??)
+    }
+}
+
+impl super::LyricParse for CommandProvider {
+    fn parse_lyric(&self, store: &LyricStore) -> LyricOwned {
+        lrc_or_none(store.lyric.as_deref())
+    }
+
+    fn parse_translated_lyric(&self, store: &LyricStore) -> LyricOwned {
+        lrc_or_none(store.tlyric.as_deref())
+    }
+}
+
+#[async_trait::async_trait]
+impl super::LyricProvider for CommandProvider {
+    async fn query_lyric(&self, id: &str) -> Result<LyricStore> {
+        let output = self.request(&CommandRequest::Query { id }).await?;
+        Ok(lyric_from_output(&output))
+    }
+
+    async fn search_song_detailed(
+        &self,
+        album: &str,
+        artists: &[&str],
+        title: &str,
+        length: Option<Duration>,
+    ) -> Result<Vec<SongInfo>> {
+        let request = CommandRequest::Search {
+            title,
+            artists,
+            album,
+            length: length.map(|length| length.as_secs_f64()),
+        };
+        let output = self.request(&request).await?;
+        Ok(songs_from_output(&output)?)
+    }
+
+    async fn search_song(&self, keyword: &str) -> Result<Vec<SongInfo>> {
+        let output = self
+            .request(&CommandRequest::SearchKeyword { keyword })
+            .await?;
+        Ok(songs_from_output(&output)?)
+    }
+
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn unique_name(&self) -> &'static str {
+        self.name
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            translation: true,
+            ..Default::default()
+        }
+    }
+
+    fn init(&self, _config: &str) -> Result<()> {
+        Ok(())
+    }
+
+    /// ids of the command have no known format
+    fn is_likely_songid(&self, _s: &str) -> bool {
+        false
+    }
+}
+
+/// writes `request` to stdin of the command, and returns its stdout
+///
+/// the command is killed once `timeout` passes
+pub async fn run_command(
+    config: &CommandProviderConfig,
+    request: &str,
+    timeout: Duration,
+) -> Result<String, Error> {
+    debug!("running lyric command {} for {request}", config.command);
+    let mut child = Command::new(&config.command)
+        .args(&config.args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+
+    let mut stdin = child.stdin.take().expect("stdin of lyric command is piped");
+    let exchange = async move {
+        // the command may exit without reading the request
+        if let Err(e) = stdin.write_all(format!("{request}\n").as_bytes()).await {
+            debug!("cannot write request to lyric command: {e}");
+        }
+        drop(stdin);
+        child.wait_with_output().await
+    };
+    let output = tokio::time::timeout(timeout, exchange)
+        .await
+        .map_err(|_| Error::Timeout(timeout))??;
+
+    if !output.status.success() {
+        return Err(Error::Failed {
+            status: output.status,
+            stderr: String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+        });
+    }
+    String::from_utf8(output.stdout).map_err(|_| Error::NotUtf8)
+}
+
+/// empty output means no result
+pub fn songs_from_output(output: &str) -> Result<Vec<SongInfo>, Error> {
+    if output.trim().is_empty() {
+        return Ok(vec![]);
+    }
+
+    let songs: Vec<CommandSong> = serde_json::from_str(output).map_err(Error::MalformedSongs)?;
+    Ok(songs
+        .into_iter()
+        .map(|song| SongInfo {
+            id: song.id,
+            title: song.title,
+            singer: song.singer,
+            album: song.album.filter(|album| !album.is_empty()),
+            length: song
+                .length
+                .and_then(|length| Duration::try_from_secs_f64(length).ok())
+                .unwrap_or_default(),
+        })
+        .collect())
+}
+
+/// lyric with translation in JSON, or lyric as it is
+pub fn lyric_from_output(output: &str) -> LyricStore {
+    match serde_json::from_str(output) {
+        Ok(CommandLyric { lyric, translation }) => LyricStore {
+            lyric,
+            tlyric: translation,
+            rlyric: None,
+            wlyric: None,
+        },
+        Err(_) => LyricStore {
+            lyric: Some(output.to_owned()),
+            tlyric: None,
+            rlyric: None,
+            wlyric: None,
+        },
+    }
+}
@@ -0,0 +1,75 @@
+//! text encoding of lyric files
+//!
+//! older lyric collections are often in GBK, Big5 or Shift_JIS.
+//! a file is decoded by its BOM, as UTF-8, in configured encodings,
+//! and finally in the guessed one, whichever first decodes without errors.
+
+use std::borrow::Cow;
+use std::sync::OnceLock;
+
+use encoding_rs::Encoding;
+
+use crate::log::{debug, warn};
+
+#[derive(thiserror::Error, Debug)]
+pub enum DecodeError {
+    #[error("text is not valid {0} as its BOM tells")]
+    Malformed(&'static str),
+    #[error("cannot detect text encoding, try to add it to `lyric-encodings`")]
+    Undetected,
+}
+
+/// encodings to try for lyric files not in UTF-8, before guessing one
+pub static LYRIC_ENCODINGS: OnceLock<Vec<&'static Encoding>> = OnceLock::new();
+
+/// decodes a lyric file with configured [`LYRIC_ENCODINGS`]
+pub fn decode_local_lyric(bytes: &[u8]) -> Result<Cow<'_, str>, DecodeError> {
+    let encodings = LYRIC_ENCODINGS.get().map(Vec::as_slice).unwrap_or_default();
+    decode_lyric(bytes, encodings)
+}
+
+pub fn decode_lyric<'a>(
+    bytes: &'a [u8],
+    encodings: &[&'static Encoding],
+) -> Result<Cow<'a, str>, DecodeError> {
+    if let Some((encoding, bom_length)) = Encoding::for_bom(bytes) {
+        return encoding
+            .decode_without_bom_handling_and_without_replacement(&bytes[bom_length..])
+            .ok_or(DecodeError::Malformed(encoding.name()));
+    }
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return Ok(Cow::Borrowed(text));
+    }
+
+    encodings
+        .iter()
+        .copied()
+        .chain([guess_encoding(bytes)])
+        .find_map(|encoding| {
+            let text = encoding.decode_without_bom_handling_and_without_replacement(bytes)?;
+            debug!("decoded lyric in {}", encoding.name());
+            Some(text)
+        })
+        .ok_or(DecodeError::Undetected)
+}
+
+/// guesses legacy encoding, never UTF-8
+pub fn guess_encoding(bytes: &[u8]) -> &'static Encoding {
+    let mut detector = chardetng::EncodingDetector::new();
+    detector.feed(bytes, true);
+    detector.guess(None, false)
+}
+
+/// looks up encodings by WHATWG labels, like `gbk` or `shift_jis`
+pub fn encodings_from_labels(labels: &[String]) -> Vec<&'static Encoding> {
+    labels
+        .iter()
+        .filter_map(|label| {
+            let encoding = Encoding::for_label(label.trim().as_bytes());
+            if encoding.is_none() {
+                warn!("unknown encoding: {label}");
+            }
+            encoding
+        })
+        .collect()
+}
@@ -0,0 +1,59 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// a lyric provider over HTTP, paths are like `data.songs.0.id`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct HttpProviderConfig {
+    /// ASCII id to put in `lyric-search-source`
+    pub id: String,
+    /// display name, same as `id` if empty
+    #[serde(default)]
+    pub name: String,
+
+    /// with `{title}`, `{artist}`, `{album}` and `{duration}` in seconds,
+    /// which is empty if unknown
+    pub search_url: String,
+    /// path of the result list in search response, empty for the response itself
+    #[serde(default)]
+    pub results_path: String,
+    /// paths below are relative to each result
+    pub id_path: String,
+    pub title_path: String,
+    pub artist_path: Option<String>,
+    pub album_path: Option<String>,
+    pub length_path: Option<String>,
+    #[serde(default)]
+    pub length_unit: LengthUnit,
+
+    /// with `{id}` of the song
+    pub lyric_url: String,
+    /// path of LRC or plain text lyric in lyric response
+    pub lrc_path: String,
+    pub translation_path: Option<String>,
+
+    /// sent with every request, like `Authorization`
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LengthUnit {
+    #[default]
+    Seconds,
+    Milliseconds,
+}
+
+impl LengthUnit {
+    /// `None` for negative, infinite or too large `length`
+    pub fn to_duration(self, length: f64) -> Option<Duration> {
+        let secs = match self {
+            LengthUnit::Seconds => length,
+            LengthUnit::Milliseconds => length / 1000.,
+        };
+        Duration::try_from_secs_f64(secs).ok()
+    }
+}
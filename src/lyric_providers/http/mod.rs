@@ -0,0 +1,216 @@
+//! lyric provider defined in config, for a lyric service over HTTP with JSON responses
+//!
+//! songs are searched by `search-url`, and lyric is fetched by `lyric-url`.
+//! fields are picked from responses by paths like `data.songs.0.id`.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use serde_json::Value;
+
+use crate::log::debug;
+use crate::tokio_spawn;
+
+use super::utils::{leak_provider_names, lrc_or_none};
+use super::{Capabilities, LyricOwned, LyricStore, SongInfo, HTTP_CLIENT};
+
+mod config;
+pub use config::{HttpProviderConfig, LengthUnit};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("no result list at `{0}` of search response")]
+    NoResultList(String),
+}
+
+pub struct HttpProvider {
+    id: &'static str,
+    name: &'static str,
+    config: Arc<HttpProviderConfig>,
+}
+
+impl HttpProvider {
+    pub fn new(config: HttpProviderConfig) -> Self {
+        let (id, name) = leak_provider_names(&config.id, &config.name);
+        Self {
+            id,
+            name,
+            config: Arc::new(config),
+        }
+    }
+}
+
+impl super::LyricParse for HttpProvider {
+    fn parse_lyric(&self, store: &LyricStore) -> LyricOwned {
+        lrc_or_none(store.lyric.as_deref())
+    }
+
+    fn parse_translated_lyric(&self, store: &LyricStore) -> LyricOwned {
+        lrc_or_none(store.tlyric.as_deref())
+    }
+}
+
+#[async_trait::async_trait]
+impl super::LyricProvider for HttpProvider {
+    async fn query_lyric(&self, id: &str) -> Result<LyricStore> {
+        let config = self.config.clone();
+        let url = fill_template(&config.lyric_url, &[("id", id)]);
+
+        tokio_spawn!(async move {
+            let response = get_json(&config, &url).await?;
+            Ok(lyric_from_response(&config, &response))
+        })
+        .await?
+    }
+
+    async fn search_song_detailed(
+        &self,
+        album: &str,
+        artists: &[&str],
+        title: &str,
+        length: Option<Duration>,
+    ) -> Result<Vec<SongInfo>> {
+        let config = self.config.clone();
+        let duration = length
+            .map(|length| length.as_secs().to_string())
+            .unwrap_or_default();
+        let url = fill_template(
+            &config.search_url,
+            &[
+                ("title", title),
+                ("artist", &artists.join(", ")),
+                ("album", album),
+                ("duration", &duration),
+            ],
+        );
+
+        tokio_spawn!(async move {
+            let response = get_json(&config, &url).await?;
+            Ok(songs_from_response(&config, &response)?)
+        })
+        .await?
+    }
+
+    async fn search_song(&self, keyword: &str) -> Result<Vec<SongInfo>> {
+        self.search_song_detailed("", &[], keyword, None).await
+    }
+
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn unique_name(&self) -> &'static str {
+        self.name
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            translation: self.config.translation_path.is_some(),
+            ..Default::default()
+        }
+    }
+
+    fn init(&self, _config: &str) -> Result<()> {
+        Ok(())
+    }
+
+    /// ids of the service have no known format
+    fn is_likely_songid(&self, _s: &str) -> bool {
+        false
+    }
+}
+
+async fn get_json(config: &HttpProviderConfig, url: &str) -> Result<Value> {
+    debug!("requesting {url}");
+    let mut request = HTTP_CLIENT.get(url).timeout(REQUEST_TIMEOUT);
+    for (name, value) in &config.headers {
+        request = request.header(name, value);
+    }
+    Ok(request.send().await?.error_for_status()?.json().await?)
+}
+
+/// replaces `{name}` in `template` with URL encoded value
+pub fn fill_template(template: &str, values: &[(&str, &str)]) -> String {
+    values
+        .iter()
+        .fold(template.to_owned(), |filled, (name, value)| {
+            // `+` means space only in query, but `%20` means space everywhere
+            let value: String = url::form_urlencoded::byte_serialize(value.as_bytes())
+                .collect::<String>()
+                .replace('+', "%20");
+            filled.replace(&format!("{{{name}}}"), &value)
+        })
+}
+
+/// looks up `path` like `data.songs.0.id`, empty `path` is `value` itself
+pub fn json_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.')
+        .filter(|key| !key.is_empty())
+        .try_fold(value, |value, key| match value {
+            Value::Array(items) => items.get(key.parse::<usize>().ok()?),
+            _ => value.get(key),
+        })
+}
+
+/// strings and numbers as they are, and lists of them joined with `, `
+fn json_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        Value::Array(items) => {
+            let texts: Vec<_> = items.iter().filter_map(json_text).collect();
+            (!texts.is_empty()).then(|| texts.join(", "))
+        }
+        _ => None,
+    }
+}
+
+fn text_at(value: &Value, path: Option<&str>) -> Option<String> {
+    json_text(json_path(value, path?)?)
+}
+
+/// results without id or title are skipped
+pub fn songs_from_response(
+    config: &HttpProviderConfig,
+    response: &Value,
+) -> Result<Vec<SongInfo>, Error> {
+    let Some(Value::Array(results)) = json_path(response, &config.results_path) else {
+        return Err(Error::NoResultList(config.results_path.clone()));
+    };
+
+    let songs = results
+        .iter()
+        .filter_map(|result| {
+            let length = config
+                .length_path
+                .as_deref()
+                .and_then(|path| json_path(result, path))
+                .and_then(|length| match length {
+                    Value::String(length) => length.trim().parse().ok(),
+                    length => length.as_f64(),
+                })
+                .and_then(|length| config.length_unit.to_duration(length));
+
+            Some(SongInfo {
+                id: text_at(result, Some(&config.id_path))?,
+                title: text_at(result, Some(&config.title_path))?,
+                singer: text_at(result, config.artist_path.as_deref()).unwrap_or_default(),
+                album: text_at(result, config.album_path.as_deref()),
+                length: length.unwrap_or_default(),
+            })
+        })
+        .collect();
+    Ok(songs)
+}
+
+pub fn lyric_from_response(config: &HttpProviderConfig, response: &Value) -> LyricStore {
+    LyricStore {
+        lyric: text_at(response, Some(&config.lrc_path)),
+        tlyric: text_at(response, config.translation_path.as_deref()),
+        rlyric: None,
+        wlyric: None,
+    }
+}
@@ -0,0 +1,166 @@
+//! lyric frames of ID3v2 tag
+//!
+//! SYLT holds timed text, either a line or a syllable per entry.
+//! Both SYLT and USLT are tagged with ISO-639-2 language code.
+
+use std::time::Duration;
+
+use super::{LyricLineOwned, LyricOwned, LyricWordOwned};
+
+/// samples in an MPEG-1 Layer III frame
+const SAMPLES_PER_MPEG_FRAME: u64 = 1152;
+
+/// converts SYLT timestamp in MPEG frames
+pub fn mpeg_frames_to_duration(frames: u32, sample_rate: u32) -> Option<Duration> {
+    let ms = (u64::from(frames) * SAMPLES_PER_MPEG_FRAME * 1000).checked_div(sample_rate.into())?;
+    Some(Duration::from_millis(ms))
+}
+
+/// builds lyric from SYLT entries
+///
+/// entries are lines, unless some of them contain line breaks,
+/// in which case they are syllables, and a line break starts a new line
+pub fn sylt_lyric(entries: impl IntoIterator<Item = (Duration, String)>) -> LyricOwned {
+    let entries: Vec<_> = entries.into_iter().collect();
+    let is_line_break = |c: char| c == '\n' || c == '\r';
+
+    let mut lines = vec![];
+    if !entries.iter().any(|(_, text)| text.contains(is_line_break)) {
+        lines = entries
+            .into_iter()
+            .map(|(start_time, text)| LyricLineOwned {
+                text: text.trim().to_owned(),
+                start_time,
+                ..Default::default()
+            })
+            .collect();
+    } else {
+        let mut words = vec![];
+        for (start_time, text) in entries {
+            if text.starts_with(is_line_break) {
+                lines.extend(words_to_line(std::mem::take(&mut words)));
+            }
+            let word = text.trim_matches(is_line_break);
+            if !word.is_empty() {
+                words.push(LyricWordOwned {
+                    text: word.to_owned(),
+                    start_time,
+                    end_time: None,
+                });
+            }
+            if text.ends_with(is_line_break) {
+                lines.extend(words_to_line(std::mem::take(&mut words)));
+            }
+        }
+        lines.extend(words_to_line(words));
+    }
+
+    if lines.is_empty() {
+        return LyricOwned::None;
+    }
+    lines.sort_by_key(|line| line.start_time);
+    LyricOwned::LineTimestamp(lines)
+}
+
+fn words_to_line(mut words: Vec<LyricWordOwned>) -> Option<LyricLineOwned> {
+    if let Some(word) = words.first_mut() {
+        word.text = word.text.trim_start().to_owned();
+    }
+    if let Some(word) = words.last_mut() {
+        word.text = word.text.trim_end().to_owned();
+    }
+    words.retain(|word| !word.text.is_empty());
+
+    let start_time = words.first()?.start_time;
+    let text = words.iter().map(|word| word.text.as_str()).collect();
+    if words.len() == 1 {
+        words.clear();
+    }
+    Some(LyricLineOwned {
+        text,
+        start_time,
+        end_time: None,
+        words,
+    })
+}
+
+/// ISO-639-2 codes of the language of `locale`, like `zh-CN`
+pub fn id3_language_codes(locale: &str) -> &'static [&'static str] {
+    let language = locale
+        .split(['-', '_', '.'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    match language.as_str() {
+        "zh" => &["chi", "zho"],
+        "en" => &["eng"],
+        "ja" => &["jpn"],
+        "ko" => &["kor"],
+        "it" => &["ita"],
+        "fr" => &["fre", "fra"],
+        "de" => &["ger", "deu"],
+        "es" => &["spa"],
+        "pt" => &["por"],
+        "ru" => &["rus"],
+        _ => &[],
+    }
+}
+
+/// picks original lyric and translation from language tagged frames
+///
+/// translation is the first frame in the language of `locale`,
+/// original is the first one in other languages
+pub fn select_by_language<T>(
+    frames: Vec<(String, T)>,
+    locale: Option<&str>,
+) -> Option<(T, Option<T>)> {
+    let codes = locale.map(id3_language_codes).unwrap_or_default();
+    let is_translation =
+        |language: &str| codes.iter().any(|code| language.eq_ignore_ascii_case(code));
+
+    let original_index = frames
+        .iter()
+        .position(|(language, _)| !is_translation(language))
+        .unwrap_or(0);
+    let translation_index = frames
+        .iter()
+        .position(|(language, _)| is_translation(language))
+        .filter(|&index| index != original_index);
+
+    let mut frames: Vec<_> = frames.into_iter().map(|(_, frame)| Some(frame)).collect();
+    let translation = translation_index.and_then(|index| frames[index].take());
+    let original = frames.get_mut(original_index)?.take()?;
+    Some((original, translation))
+}
+
+/// builds the content of SYLT frame, in UTF-8 and millisecond timestamps
+///
+/// word timed lyric is written as syllables, and a line break starts a new line
+pub fn make_sylt(lines: &[LyricLineOwned], language: [u8; 3]) -> Vec<u8> {
+    // UTF-8, language, milliseconds, lyrics, empty content descriptor
+    let mut data = vec![3];
+    data.extend(language);
+    data.extend([2, 1, 0]);
+
+    let mut push_entry = |text: &str, time: Duration| {
+        data.extend(text.as_bytes());
+        data.push(0);
+        let time = u32::try_from(time.as_millis()).unwrap_or(u32::MAX);
+        data.extend(time.to_be_bytes());
+    };
+
+    let word_timed = lines.iter().any(LyricLineOwned::is_word_timed);
+    for (index, line) in lines.iter().enumerate() {
+        let line_break = if word_timed && index != 0 { "\n" } else { "" };
+        if line.is_word_timed() {
+            for (word_index, word) in line.words.iter().enumerate() {
+                let line_break = if word_index == 0 { line_break } else { "" };
+                push_entry(&format!("{line_break}{}", word.text), word.start_time);
+            }
+        } else {
+            push_entry(&format!("{line_break}{}", line.text), line.start_time);
+        }
+    }
+
+    data
+}
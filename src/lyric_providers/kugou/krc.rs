@@ -0,0 +1,202 @@
+//! KRC, word-timed lyric of Kugou
+//!
+//! a downloaded KRC is `krc1` followed by zlib compressed text, XORed with a fixed key.
+//! each lyric line of the text is like
+//!
+//! ```text
+//! [1000,2000]<0,500,0>Hello <500,1500,0>world
+//! ```
+//!
+//! where `[start,duration]` of the line are in milliseconds,
+//! and `<offset,duration,0>` of each word are relative to the line start.
+//! `[language:...]` tag is base64 encoded JSON of translation and romanization,
+//! each of which has an entry for every lyric line.
+
+use std::io::Read;
+use std::time::Duration;
+
+use base64::Engine;
+use flate2::read::ZlibDecoder;
+use serde::Deserialize;
+
+use crate::log::warn;
+use crate::lyric_providers::utils::word_timed_line;
+use crate::lyric_providers::{LyricLineOwned, LyricOwned, LyricWordOwned};
+
+const KRC_MAGIC: &[u8] = b"krc1";
+pub const KRC_KEY: [u8; 16] = [
+    0x40, 0x47, 0x61, 0x77, 0x5e, 0x32, 0x74, 0x47, 0x51, 0x36, 0x31, 0x2d, 0xce, 0xd2, 0x6e, 0x69,
+];
+
+#[derive(thiserror::Error, Debug)]
+pub enum KrcError {
+    #[error("not a KRC file")]
+    Magic,
+    #[error("malformed KRC data: {0}")]
+    Inflate(#[from] std::io::Error),
+    #[error("KRC text is not in UTF-8")]
+    Utf8(#[from] std::string::FromUtf8Error),
+}
+
+/// decrypts downloaded KRC into text
+pub fn decrypt_krc(krc: &[u8]) -> Result<String, KrcError> {
+    let krc = krc.strip_prefix(KRC_MAGIC).ok_or(KrcError::Magic)?;
+    let compressed: Vec<u8> = krc
+        .iter()
+        .zip(KRC_KEY.iter().cycle())
+        .map(|(byte, key)| byte ^ key)
+        .collect();
+
+    let mut text = vec![];
+    ZlibDecoder::new(&compressed[..]).read_to_end(&mut text)?;
+    Ok(String::from_utf8(text)?
+        .trim_start_matches('\u{feff}')
+        .to_owned())
+}
+
+#[derive(Debug, Default)]
+pub struct KrcLyric {
+    pub lyric: LyricOwned,
+    pub translation: LyricOwned,
+    pub romanization: LyricOwned,
+}
+
+#[derive(Deserialize)]
+struct KrcLanguages {
+    content: Vec<KrcLanguage>,
+}
+
+#[derive(Deserialize)]
+struct KrcLanguage {
+    /// 0 for romanization of each word, 1 for translation of each line
+    #[serde(rename = "type")]
+    kind: u8,
+    #[serde(rename = "lyricContent")]
+    lyric_content: Vec<Vec<String>>,
+}
+
+/// parses decrypted KRC text
+pub fn parse_krc(krc: &str) -> KrcLyric {
+    let mut lines = vec![];
+    let mut languages = None;
+    for line in krc.lines().map(str::trim) {
+        if let Some(language) = line
+            .strip_prefix("[language:")
+            .and_then(|language| language.strip_suffix(']'))
+        {
+            languages = parse_languages(language);
+        } else if let Some(line) = parse_krc_line(line) {
+            lines.push(line);
+        }
+    }
+
+    let mut parsed = KrcLyric::default();
+    for language in languages
+        .map(|languages| languages.content)
+        .unwrap_or_default()
+    {
+        match language.kind {
+            0 => parsed.romanization = romanized_lines(&lines, language.lyric_content),
+            1 => parsed.translation = translated_lines(&lines, language.lyric_content),
+            kind => warn!("unknown KRC language type {kind}"),
+        }
+    }
+    if !lines.is_empty() {
+        parsed.lyric = LyricOwned::LineTimestamp(lines);
+    }
+    parsed
+}
+
+fn parse_languages(language: &str) -> Option<KrcLanguages> {
+    let json = base64::engine::general_purpose::STANDARD
+        .decode(language.trim())
+        .map_err(|e| warn!("malformed KRC language tag: {e}"))
+        .ok()?;
+    serde_json::from_slice(&json)
+        .map_err(|e| warn!("malformed KRC language tag: {e}"))
+        .ok()
+}
+
+/// `[start,duration]<offset,duration,0>word...`, metadata tags are not lines
+fn parse_krc_line(line: &str) -> Option<LyricLineOwned> {
+    let (timing, words) = line.strip_prefix('[')?.split_once(']')?;
+    let (start, duration) = timing.split_once(',')?;
+    let start_time = Duration::from_millis(start.trim().parse().ok()?);
+    let end_time = start_time + Duration::from_millis(duration.trim().parse().ok()?);
+
+    let words = words
+        .split('<')
+        .filter_map(|word| {
+            let (timing, text) = word.split_once('>')?;
+            let mut timing = timing.split(',').map(|time| time.trim().parse::<u64>());
+            let start_time = start_time + Duration::from_millis(timing.next()?.ok()?);
+            let duration = Duration::from_millis(timing.next()?.ok()?);
+            Some(LyricWordOwned {
+                text: text.to_owned(),
+                start_time,
+                end_time: Some(start_time + duration),
+            })
+        })
+        .collect();
+    Some(word_timed_line(start_time, Some(end_time), words))
+}
+
+/// a line for each lyric line, empty ones are skipped
+fn translated_lines(lines: &[LyricLineOwned], content: Vec<Vec<String>>) -> LyricOwned {
+    let translated: Vec<_> = lines
+        .iter()
+        .zip(content)
+        .filter_map(|(line, translation)| {
+            let text = translation.concat().trim().to_owned();
+            (!text.is_empty()).then(|| LyricLineOwned {
+                text,
+                start_time: line.start_time,
+                end_time: line.end_time,
+                words: vec![],
+            })
+        })
+        .collect();
+
+    if translated.is_empty() {
+        LyricOwned::None
+    } else {
+        LyricOwned::LineTimestamp(translated)
+    }
+}
+
+/// words for each lyric line, timed as the original words if they are as many
+fn romanized_lines(lines: &[LyricLineOwned], content: Vec<Vec<String>>) -> LyricOwned {
+    let romanized: Vec<_> = lines
+        .iter()
+        .zip(content)
+        .filter_map(|(line, romanized)| {
+            if romanized.len() != line.words.len() {
+                let text = romanized.concat().trim().to_owned();
+                return (!text.is_empty()).then(|| LyricLineOwned {
+                    text,
+                    start_time: line.start_time,
+                    end_time: line.end_time,
+                    words: vec![],
+                });
+            }
+
+            let words = line
+                .words
+                .iter()
+                .zip(romanized)
+                .map(|(word, text)| LyricWordOwned {
+                    text,
+                    ..word.clone()
+                })
+                .collect();
+            let line = word_timed_line(line.start_time, line.end_time, words);
+            (!line.text.is_empty()).then_some(line)
+        })
+        .collect();
+
+    if romanized.is_empty() {
+        LyricOwned::None
+    } else {
+        LyricOwned::LineTimestamp(romanized)
+    }
+}
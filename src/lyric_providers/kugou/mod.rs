@@ -0,0 +1,229 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use base64::Engine;
+use serde::Deserialize;
+use url::Url;
+
+use crate::log::debug;
+use crate::tokio_spawn;
+
+use super::{Capabilities, LyricOwned, LyricStore, SongInfo, HTTP_CLIENT};
+
+pub mod krc;
+use krc::{decrypt_krc, parse_krc};
+
+const SEARCH_URL: &str = "https://mobilecdn.kugou.com/api/v3/search/song";
+const LYRIC_SEARCH_URL: &str = "https://krcs.kugou.com/search";
+const LYRIC_DOWNLOAD_URL: &str = "https://lyrics.kugou.com/download";
+
+#[derive(Clone, Copy)]
+pub struct Kugou;
+
+#[derive(Debug, Deserialize)]
+struct SearchResp {
+    data: Option<SearchData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchData {
+    #[serde(default)]
+    info: Vec<SearchSong>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchSong {
+    hash: String,
+    songname: String,
+    #[serde(default)]
+    singername: String,
+    #[serde(default)]
+    album_name: String,
+    /// in seconds
+    #[serde(default)]
+    duration: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct LyricSearchResp {
+    #[serde(default)]
+    candidates: Vec<LyricCandidate>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LyricCandidate {
+    /// either string or number
+    id: serde_json::Value,
+    accesskey: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct LyricDownloadResp {
+    /// base64 encoded KRC
+    #[serde(default)]
+    content: String,
+}
+
+#[async_trait::async_trait]
+impl super::LyricProvider for Kugou {
+    fn init(&self, _config: &str) -> Result<()> {
+        Ok(())
+    }
+
+    fn id(&self) -> &'static str {
+        "kugou"
+    }
+
+    fn unique_name(&self) -> &'static str {
+        "酷狗音乐"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            translation: true,
+            word_timing: true,
+            romanization: true,
+        }
+    }
+
+    async fn search_song_detailed(
+        &self,
+        _album: &str,
+        artists: &[&str],
+        title: &str,
+        _length: Option<Duration>,
+    ) -> Result<Vec<SongInfo>> {
+        // album in keyword makes Kugou find nothing quite often
+        let keyword = format!("{} {title}", artists.join(" "));
+        self.search_song(keyword.trim()).await
+    }
+
+    /// `id` is the hash of song
+    async fn query_lyric(&self, id: &str) -> Result<LyricStore> {
+        let hash = id.to_owned();
+        tokio_spawn!(async move {
+            let url = Url::parse_with_params(
+                LYRIC_SEARCH_URL,
+                [
+                    ("ver", "1"),
+                    ("man", "yes"),
+                    ("client", "mobi"),
+                    ("hash", hash.as_str()),
+                ],
+            )?;
+            let resp: LyricSearchResp = HTTP_CLIENT.get(url).send().await?.json().await?;
+            let LyricCandidate { id, accesskey } = resp
+                .candidates
+                .into_iter()
+                .next()
+                .ok_or(super::Error::NoResult)?;
+            let id = match id {
+                serde_json::Value::String(id) => id,
+                id => id.to_string(),
+            };
+
+            let url = Url::parse_with_params(
+                LYRIC_DOWNLOAD_URL,
+                [
+                    ("ver", "1"),
+                    ("client", "pc"),
+                    ("id", id.as_str()),
+                    ("accesskey", accesskey.as_str()),
+                    ("fmt", "krc"),
+                    ("charset", "utf8"),
+                ],
+            )?;
+            let resp = HTTP_CLIENT.get(url).send().await?.text().await?;
+            let krc = parse_download_resp(&resp)?;
+
+            debug!("lyric query result: {krc}");
+            Ok(LyricStore {
+                lyric: Some(krc),
+                tlyric: None,
+                rlyric: None,
+                wlyric: None,
+            })
+        })
+        .await?
+    }
+
+    async fn search_song(&self, keyword: &str) -> Result<Vec<SongInfo>> {
+        let keyword = keyword.to_owned();
+        tokio_spawn!(async move {
+            debug!("search keyword: {keyword}");
+
+            let url = Url::parse_with_params(
+                SEARCH_URL,
+                [
+                    ("format", "json"),
+                    ("keyword", keyword.as_str()),
+                    ("page", "1"),
+                    ("pagesize", "20"),
+                    ("showtype", "1"),
+                ],
+            )?;
+            let resp = HTTP_CLIENT.get(url).send().await?.text().await?;
+            parse_search_resp(&resp)
+        })
+        .await?
+    }
+
+    fn is_likely_songid(&self, s: &str) -> bool {
+        s.len() == 32 && s.bytes().all(|b| b.is_ascii_hexdigit())
+    }
+}
+
+/// songs in response of [`SEARCH_URL`]
+pub fn parse_search_resp(resp: &str) -> Result<Vec<SongInfo>> {
+    let resp: SearchResp = serde_json::from_str(resp)?;
+    Ok(resp
+        .data
+        .ok_or(super::Error::NoResult)?
+        .info
+        .into_iter()
+        .map(|song| SongInfo {
+            id: song.hash,
+            title: song.songname,
+            singer: song.singername,
+            album: Some(song.album_name).filter(|album| !album.is_empty()),
+            length: Duration::from_secs(song.duration),
+        })
+        .collect())
+}
+
+/// decrypted KRC in response of [`LYRIC_DOWNLOAD_URL`]
+pub fn parse_download_resp(resp: &str) -> Result<String> {
+    let resp: LyricDownloadResp = serde_json::from_str(resp)?;
+    if resp.content.is_empty() {
+        return Err(super::Error::NoResult)?;
+    }
+    let krc = base64::engine::general_purpose::STANDARD.decode(resp.content)?;
+    Ok(decrypt_krc(&krc)?)
+}
+
+/// lyric, translation and romanization are all in KRC
+impl super::LyricParse for Kugou {
+    fn parse_lyric(&self, store: &LyricStore) -> LyricOwned {
+        store
+            .lyric
+            .as_deref()
+            .map(|krc| parse_krc(krc).lyric)
+            .unwrap_or_default()
+    }
+
+    fn parse_translated_lyric(&self, store: &LyricStore) -> LyricOwned {
+        store
+            .lyric
+            .as_deref()
+            .map(|krc| parse_krc(krc).translation)
+            .unwrap_or_default()
+    }
+
+    fn parse_romanized_lyric(&self, store: &LyricStore) -> LyricOwned {
+        store
+            .lyric
+            .as_deref()
+            .map(|krc| parse_krc(krc).romanization)
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,22 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct LyricLibraryConfig {
+    /// folders to index, including their subfolders
+    pub dirs: Vec<PathBuf>,
+    /// patterns of file names without extension, tried in order,
+    /// with `{title}`, `{artist}` and `{album}`
+    pub filename_patterns: Vec<String>,
+}
+
+impl Default for LyricLibraryConfig {
+    fn default() -> Self {
+        Self {
+            dirs: vec![],
+            filename_patterns: vec!["{artist} - {title}".into(), "{title}".into()],
+        }
+    }
+}
@@ -0,0 +1,438 @@
+//! lyric files in folders of `lyric-library`, not necessarily next to the music
+//!
+//! `.lrc`, `.ttml`, `.srt` and `.vtt` files are indexed by their `[ti:]`, `[ar:]`,
+//! `[al:]` and `[length:]` tags, or by their file names like `{artist} - {title}.lrc`.
+//! songs are searched in the index, so players giving only an URL can use local lyric.
+//!
+//! the index is built once in background at startup,
+//! files added later are found after restart.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+
+use crate::log::{error, info, warn};
+
+use super::encoding::decode_local_lyric;
+use super::subtitle::parse_subtitle;
+use super::ttml::parse_ttml;
+use super::utils::{lrc_metadata, lrc_or_plain, LrcMetadata};
+use super::{Capabilities, LyricOwned, LyricStore, SongInfo};
+
+mod config;
+pub use config::LyricLibraryConfig;
+
+/// extensions of lyric files to index
+pub const LIBRARY_EXTENSIONS: &[&str] = &["lrc", "ttml", "srt", "vtt"];
+
+static LIBRARY_CONFIG: OnceLock<LyricLibraryConfig> = OnceLock::new();
+static LIBRARY_INDEX: OnceLock<LibraryIndex> = OnceLock::new();
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("lyric library already initialized")]
+    Inited,
+}
+
+#[derive(Clone, Copy)]
+pub struct LyricLibrary;
+
+/// builds the index on first call, other callers wait for it
+fn library_index() -> &'static LibraryIndex {
+    LIBRARY_INDEX.get_or_init(|| {
+        let config = LIBRARY_CONFIG.get().cloned().unwrap_or_default();
+        let started = Instant::now();
+        let index = LibraryIndex::build(&config);
+        info!(
+            "indexed {} lyric files in {:?}",
+            index.entries.len(),
+            started.elapsed()
+        );
+        index
+    })
+}
+
+impl super::LyricParse for LyricLibrary {
+    fn parse_lyric(&self, store: &LyricStore) -> LyricOwned {
+        parse_library_lyric(store.lyric.as_deref()).0
+    }
+
+    fn parse_translated_lyric(&self, store: &LyricStore) -> LyricOwned {
+        parse_library_lyric(store.lyric.as_deref()).1
+    }
+
+    fn parse_lyric_metadata(&self, store: &LyricStore) -> LrcMetadata {
+        store
+            .lyric
+            .as_deref()
+            .map(|lyric| lrc_metadata(lyric.lines()))
+            .unwrap_or_default()
+    }
+}
+
+#[async_trait::async_trait]
+impl super::LyricProvider for LyricLibrary {
+    fn init(&self, config: &str) -> Result<()> {
+        let config: LyricLibraryConfig = serde_json::from_str(config)?;
+        if config.dirs.is_empty() {
+            warn!("lyric library is enabled, but `lyric-library.dirs` is empty");
+        }
+        LIBRARY_CONFIG.set(config).map_err(|_| Error::Inited)?;
+
+        std::thread::spawn(library_index);
+        Ok(())
+    }
+
+    fn id(&self) -> &'static str {
+        "library"
+    }
+
+    fn unique_name(&self) -> &'static str {
+        "Lyric Library"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        // TTML may carry translation
+        Capabilities {
+            translation: true,
+            word_timing: true,
+            romanization: false,
+        }
+    }
+
+    fn config_section(&self) -> Option<&'static str> {
+        Some("lyric-library")
+    }
+
+    async fn search_song_detailed(
+        &self,
+        _album: &str,
+        artists: &[&str],
+        title: &str,
+        _length: Option<Duration>,
+    ) -> Result<Vec<SongInfo>> {
+        let artists: Vec<String> = artists.iter().map(|&artist| artist.to_owned()).collect();
+        let title = title.to_owned();
+        Ok(tokio::task::spawn_blocking(move || {
+            let artists: Vec<&str> = artists.iter().map(String::as_str).collect();
+            library_index().search(&artists, &title)
+        })
+        .await?)
+    }
+
+    async fn search_song(&self, keyword: &str) -> Result<Vec<SongInfo>> {
+        let keyword = keyword.to_owned();
+        Ok(tokio::task::spawn_blocking(move || library_index().search_keyword(&keyword)).await?)
+    }
+
+    /// song ids are paths of lyric files
+    async fn query_lyric(&self, id: &str) -> Result<LyricStore> {
+        let path = PathBuf::from(id);
+        let lyric = tokio::task::spawn_blocking(move || {
+            let bytes = fs::read(&path)?;
+            anyhow::Ok(decode_local_lyric(&bytes)?.into_owned())
+        })
+        .await??;
+
+        Ok(LyricStore {
+            lyric: Some(lyric),
+            tlyric: None,
+            rlyric: None,
+            wlyric: None,
+        })
+    }
+
+    fn is_likely_songid(&self, s: &str) -> bool {
+        let path = Path::new(s);
+        path.is_absolute() && has_library_extension(path)
+    }
+}
+
+fn has_library_extension(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            LIBRARY_EXTENSIONS
+                .iter()
+                .any(|known| known.eq_ignore_ascii_case(extension))
+        })
+}
+
+/// tells the format by content, as ids may be typed in search window
+///
+/// returns original lyric and translation
+pub fn parse_library_lyric(lyric: Option<&str>) -> (LyricOwned, LyricOwned) {
+    let Some(lyric) = lyric.map(|lyric| lyric.trim_start_matches('\u{feff}')) else {
+        return Default::default();
+    };
+
+    if lyric.trim_start().starts_with('<') {
+        return parse_ttml(lyric)
+            .map_err(|e| error!("cannot parse TTML lyric: {e}"))
+            .unwrap_or_default();
+    }
+    if lyric.contains("-->") {
+        match parse_subtitle(lyric) {
+            Ok(lines) if !lines.is_empty() => {
+                return (LyricOwned::LineTimestamp(lines), LyricOwned::None)
+            }
+            Ok(_) => (),
+            Err(e) => error!("cannot parse subtitle lyric: {e}"),
+        }
+    }
+    (lrc_or_plain(lyric).into_owned(), LyricOwned::None)
+}
+
+/// fields taken from a file name by a pattern
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FilenameFields {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+}
+
+enum PatternToken<'a> {
+    Literal(&'a str),
+    Field(&'a str),
+}
+
+fn tokenize_pattern(pattern: &str) -> Vec<PatternToken<'_>> {
+    let mut tokens = vec![];
+    let mut rest = pattern;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        if start > 0 {
+            tokens.push(PatternToken::Literal(&rest[..start]));
+        }
+        tokens.push(PatternToken::Field(&rest[start + 1..start + len]));
+        rest = &rest[start + len + 1..];
+    }
+    if !rest.is_empty() {
+        tokens.push(PatternToken::Literal(rest));
+    }
+    tokens
+}
+
+/// fields take as few characters as they can, and cannot be empty
+fn match_tokens<'p, 'a>(
+    tokens: &[PatternToken<'p>],
+    text: &'a str,
+    captures: &mut Vec<(&'p str, &'a str)>,
+) -> bool {
+    match tokens {
+        [] => text.is_empty(),
+        [PatternToken::Literal(literal), rest @ ..] => text
+            .strip_prefix(literal)
+            .is_some_and(|text| match_tokens(rest, text, captures)),
+        [PatternToken::Field(_), ..] if text.is_empty() => false,
+        [PatternToken::Field(name), rest @ ..] => {
+            let ends = text.char_indices().skip(1).map(|(end, _)| end);
+            for end in ends.chain([text.len()]) {
+                captures.push((*name, &text[..end]));
+                if match_tokens(rest, &text[end..], captures) {
+                    return true;
+                }
+                captures.pop();
+            }
+            false
+        }
+    }
+}
+
+/// matches file name without extension against a pattern like `{artist} - {title}`
+///
+/// unknown fields like `{track}` match anything, and are ignored
+pub fn match_filename(pattern: &str, stem: &str) -> Option<FilenameFields> {
+    let tokens = tokenize_pattern(pattern);
+    let mut captures = vec![];
+    if !match_tokens(&tokens, stem, &mut captures) {
+        return None;
+    }
+
+    let mut fields = FilenameFields::default();
+    for (name, value) in captures {
+        let value = Some(value.trim().to_owned()).filter(|value| !value.is_empty());
+        match name {
+            "title" => fields.title = value,
+            "artist" => fields.artist = value,
+            "album" => fields.album = value,
+            _ => (),
+        }
+    }
+    Some(fields)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LibraryEntry {
+    pub path: PathBuf,
+    pub title: String,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub length: Option<Duration>,
+}
+
+impl LibraryEntry {
+    /// tags of LRC take precedence over the file name,
+    /// which is the title if no pattern matches
+    ///
+    /// returns `None` for paths not in UTF-8, as they cannot be song ids
+    pub fn from_file(path: &Path, patterns: &[String]) -> Option<Self> {
+        path.to_str()?;
+        let stem = path.file_stem()?.to_str()?;
+        let fields = patterns
+            .iter()
+            .find_map(|pattern| match_filename(pattern, stem))
+            .unwrap_or_default();
+
+        let is_lrc = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("lrc"));
+        let metadata = is_lrc
+            .then(|| fs::read(path).ok())
+            .flatten()
+            .and_then(|bytes| {
+                decode_local_lyric(&bytes)
+                    .map(|lyric| lrc_metadata(lyric.lines()))
+                    .ok()
+            })
+            .unwrap_or_default();
+        let non_empty = |value: Option<String>| value.filter(|value| !value.is_empty());
+
+        Some(Self {
+            path: path.to_owned(),
+            title: non_empty(metadata.title)
+                .or(fields.title)
+                .unwrap_or_else(|| stem.trim().to_owned()),
+            artist: non_empty(metadata.artist).or(fields.artist),
+            album: non_empty(metadata.album).or(fields.album),
+            length: metadata.length,
+        })
+    }
+
+    fn to_song_info(&self) -> SongInfo {
+        SongInfo {
+            id: self.path.to_string_lossy().into_owned(),
+            title: self.title.clone(),
+            singer: self.artist.clone().unwrap_or_default(),
+            album: self.album.clone(),
+            length: self.length.unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct LibraryIndex {
+    pub entries: Vec<LibraryEntry>,
+}
+
+/// lowercase, with runs of whitespace as one space
+fn normalize(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// either contains the other, empty text matches nothing
+fn loosely_match(a: &str, b: &str) -> bool {
+    !a.is_empty() && !b.is_empty() && (a.contains(b) || b.contains(a))
+}
+
+impl LibraryIndex {
+    pub fn build(config: &LyricLibraryConfig) -> Self {
+        let mut paths = vec![];
+        for dir in &config.dirs {
+            collect_lyric_files(dir, &mut paths);
+        }
+        paths.sort();
+        paths.dedup();
+
+        let entries = paths
+            .iter()
+            .filter_map(|path| LibraryEntry::from_file(path, &config.filename_patterns))
+            .collect();
+        Self { entries }
+    }
+
+    /// entries with loosely matched title and artist, exact titles first
+    ///
+    /// entries without artist match any artist, so do queries without artist
+    pub fn search(&self, artists: &[&str], title: &str) -> Vec<SongInfo> {
+        let title = normalize(title);
+        let artists: Vec<_> = artists.iter().map(|artist| normalize(artist)).collect();
+
+        let mut matched: Vec<_> = self
+            .entries
+            .iter()
+            .filter_map(|entry| {
+                let entry_title = normalize(&entry.title);
+                if !loosely_match(&entry_title, &title) {
+                    return None;
+                }
+                if let Some(entry_artist) = &entry.artist {
+                    let entry_artist = normalize(entry_artist);
+                    if !artists.is_empty()
+                        && !artists
+                            .iter()
+                            .any(|artist| loosely_match(&entry_artist, artist))
+                    {
+                        return None;
+                    }
+                }
+                Some((entry_title != title, entry))
+            })
+            .collect();
+        matched.sort_by_key(|(inexact, _)| *inexact);
+
+        matched
+            .into_iter()
+            .map(|(_, entry)| entry.to_song_info())
+            .collect()
+    }
+
+    /// entries with every word of `keyword` in their title, artist or album
+    pub fn search_keyword(&self, keyword: &str) -> Vec<SongInfo> {
+        let keyword = normalize(keyword);
+        self.entries
+            .iter()
+            .filter(|entry| {
+                let text = normalize(&format!(
+                    "{} {} {}",
+                    entry.title,
+                    entry.artist.as_deref().unwrap_or_default(),
+                    entry.album.as_deref().unwrap_or_default()
+                ));
+                keyword.split(' ').all(|word| text.contains(word))
+            })
+            .map(LibraryEntry::to_song_info)
+            .collect()
+    }
+}
+
+/// symlinked folders are not followed, to avoid loops
+fn collect_lyric_files(dir: &Path, paths: &mut Vec<PathBuf>) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            warn!("cannot read lyric library folder {dir:?}: {e}");
+            return;
+        }
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            collect_lyric_files(&path, paths);
+        } else if has_library_extension(&path) && path.is_file() {
+            paths.push(path);
+        }
+    }
+}
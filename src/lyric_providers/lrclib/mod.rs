@@ -6,7 +6,7 @@ use lrclib_api_rs::types::{ErrorResponse, GetLyricsResponse, LyricsData};
 use lrclib_api_rs::LRCLibAPI;
 use reqwest::Client;
 
-use super::{Lyric, LyricOwned, LyricStore, SongInfo};
+use super::{Capabilities, Lyric, LyricOwned, LyricStore, SongInfo};
 use crate::tokio_spawn;
 
 pub struct LRCLib;
@@ -36,9 +36,18 @@ impl super::LyricProvider for LRCLib {
             let resp = REQWEST_CLIENT.get(req.uri().to_string()).send().await?;
             let result: GetLyricsResponse = resp.json().await?;
             match result {
-                GetLyricsResponse::Success(LyricsData { synced_lyrics, .. }) => Ok(LyricStore {
-                    lyric: synced_lyrics,
+                GetLyricsResponse::Success(LyricsData {
+                    synced_lyrics,
+                    plain_lyrics,
+                    ..
+                }) => Ok(LyricStore {
+                    // unsynced lyric is better than nothing
+                    lyric: synced_lyrics
+                        .filter(|lyric| !lyric.is_empty())
+                        .or(plain_lyrics),
                     tlyric: None,
+                    rlyric: None,
+                    wlyric: None,
                 }),
                 GetLyricsResponse::Error(ErrorResponse { message, .. }) => {
                     crate::log::debug!("query failed: {message}");
@@ -53,6 +62,7 @@ impl super::LyricProvider for LRCLib {
         album: &str,
         artists: &[&str],
         title: &str,
+        _length: Option<Duration>,
     ) -> Result<Vec<SongInfo>> {
         let album = if album.is_empty() {
             None
@@ -90,10 +100,16 @@ impl super::LyricProvider for LRCLib {
         })
         .await?
     }
+    fn id(&self) -> &'static str {
+        "lrclib"
+    }
     fn unique_name(&self) -> &'static str {
         "LRCLib"
     }
-    fn init(self, _config: &str) -> Result<()> {
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::default()
+    }
+    fn init(&self, _config: &str) -> Result<()> {
         Ok(())
     }
     fn is_likely_songid(&self, s: &str) -> bool {
@@ -104,13 +120,7 @@ impl super::LyricProvider for LRCLib {
 fn verify_lyric(lyric: Option<&str>) -> Lyric<'_> {
     match lyric {
         Some("") | None => super::Lyric::None,
-        Some(lyric) => {
-            if let Ok(parsed) = super::utils::lrc_iter(lyric.lines()) {
-                Lyric::LineTimestamp(parsed)
-            } else {
-                Lyric::NoTimestamp
-            }
-        }
+        Some(lyric) => super::utils::lrc_or_plain(lyric),
     }
 }
 
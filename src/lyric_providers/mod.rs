@@ -1,26 +1,48 @@
 mod strip_util;
-pub use strip_util::strip_extended_timestamps;
+pub use strip_util::{split_extended_timestamps, strip_extended_timestamps};
 
+pub mod encoding;
+pub mod id3;
+pub mod registry;
+pub mod subtitle;
+pub mod ttml;
 pub mod utils;
 use anyhow::Result;
 use strum::EnumIs;
 
 pub mod dict;
 
+pub mod command;
+pub mod http;
+pub mod kugou;
+pub mod library;
 pub mod lrclib;
 pub mod netease;
 pub mod qqmusic;
 
 use std::borrow::Cow;
 use std::fmt::Debug;
+use std::sync::LazyLock;
 use std::time::Duration;
 
+use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
+pub use registry::Capabilities;
+
+/// shared by providers requesting over HTTP, so that connections are reused
+static HTTP_CLIENT: LazyLock<Client> = LazyLock::new(|| {
+    Client::builder()
+        .user_agent("Waylyrics/0.1")
+        .build()
+        .unwrap_or_default()
+});
+
 #[derive(Debug)]
 pub enum Lyric<'a> {
     None,
-    NoTimestamp,
+    /// plain text lyric
+    NoTimestamp(Cow<'a, str>),
     LineTimestamp(Vec<LyricLine<'a>>),
 }
 
@@ -28,21 +50,68 @@ pub enum Lyric<'a> {
 pub struct LyricLine<'a> {
     pub text: Cow<'a, str>,
     pub start_time: Duration,
+    pub end_time: Option<Duration>,
+    /// word-level (karaoke) timings, empty if the source only has line timestamps
+    pub words: Vec<LyricWord<'a>>,
+}
+
+#[derive(Debug)]
+pub struct LyricWord<'a> {
+    pub text: Cow<'a, str>,
+    pub start_time: Duration,
+    pub end_time: Option<Duration>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Default, EnumIs)]
-#[serde(tag = "type", content = "content")]
+#[serde(tag = "type", content = "content", from = "CachedLyricOwned")]
 pub enum LyricOwned {
     #[default]
     None,
-    NoTimestamp,
+    /// plain text lyric
+    NoTimestamp(String),
     LineTimestamp(Vec<LyricLineOwned>),
 }
 
+/// `LyricOwned` as in caches, which used to write `NoTimestamp` without its text
+#[derive(Deserialize)]
+#[serde(tag = "type", content = "content")]
+enum CachedLyricOwned {
+    None,
+    NoTimestamp(Option<String>),
+    LineTimestamp(Vec<LyricLineOwned>),
+}
+
+impl From<CachedLyricOwned> for LyricOwned {
+    fn from(cached: CachedLyricOwned) -> Self {
+        match cached {
+            CachedLyricOwned::None | CachedLyricOwned::NoTimestamp(None) => LyricOwned::None,
+            CachedLyricOwned::NoTimestamp(Some(text)) => LyricOwned::NoTimestamp(text),
+            CachedLyricOwned::LineTimestamp(lines) => LyricOwned::LineTimestamp(lines),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
 pub struct LyricLineOwned {
     pub text: String,
     pub start_time: Duration,
+    /// `None` if the source does not tell when the line ends
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_time: Option<Duration>,
+    /// word-level (karaoke) timings
+    ///
+    /// when not empty, concatenated `text`s of the words equal to the line's `text`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub words: Vec<LyricWordOwned>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct LyricWordOwned {
+    pub text: String,
+    pub start_time: Duration,
+    /// `None` means the word lasts until the next word starts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_time: Option<Duration>,
 }
 
 #[derive(Debug)]
@@ -57,15 +126,26 @@ pub struct SongInfo {
 #[async_trait::async_trait]
 pub trait LyricProvider: LyricParse + Send + Sync {
     async fn query_lyric(&self, id: &str) -> Result<LyricStore>;
+    /// `length` of the track, if known
     async fn search_song_detailed(
         &self,
         album: &str,
         artists: &[&str],
         title: &str,
+        length: Option<Duration>,
     ) -> Result<Vec<SongInfo>>;
     async fn search_song(&self, keyword: &str) -> Result<Vec<SongInfo>>;
+    /// stable ASCII id, used in `lyric-search-source`
+    fn id(&self) -> &'static str;
+    /// display name
     fn unique_name(&self) -> &'static str;
-    fn init(self, config: &str) -> Result<()>;
+    fn capabilities(&self) -> Capabilities;
+    /// section of config passed to [`LyricProvider::init`], if it has something to configure
+    fn config_section(&self) -> Option<&'static str> {
+        None
+    }
+    /// called once at startup if enabled, with its config section in JSON
+    fn init(&self, config: &str) -> Result<()>;
     fn is_likely_songid(&self, s: &str) -> bool;
 }
 
@@ -78,13 +158,21 @@ impl Debug for dyn LyricProvider {
 pub trait LyricParse {
     fn parse_lyric(&self, store: &LyricStore) -> LyricOwned;
     fn parse_translated_lyric(&self, store: &LyricStore) -> LyricOwned;
+    /// most providers do not have romanized lyrics
+    fn parse_romanized_lyric(&self, _store: &LyricStore) -> LyricOwned {
+        LyricOwned::None
+    }
+    /// tags like `[offset:]`, only lyric files have them
+    fn parse_lyric_metadata(&self, _store: &LyricStore) -> utils::LrcMetadata {
+        utils::LrcMetadata::default()
+    }
 }
 
 impl Lyric<'_> {
     pub fn into_owned(self) -> LyricOwned {
         match self {
             Lyric::None => LyricOwned::None,
-            Lyric::NoTimestamp => LyricOwned::NoTimestamp,
+            Lyric::NoTimestamp(text) => LyricOwned::NoTimestamp(text.into()),
             Lyric::LineTimestamp(lyrics) => LyricOwned::LineTimestamp(
                 lyrics
                     .into_iter()
@@ -96,17 +184,64 @@ impl Lyric<'_> {
 }
 
 impl LyricLine<'_> {
-    pub fn into_owned(Self { text, start_time }: Self) -> LyricLineOwned {
+    pub fn into_owned(
+        Self {
+            text,
+            start_time,
+            end_time,
+            words,
+        }: Self,
+    ) -> LyricLineOwned {
         LyricLineOwned {
             text: text.into(),
             start_time,
+            end_time,
+            words: words.into_iter().map(LyricWord::<'_>::into_owned).collect(),
         }
     }
 }
 
+impl LyricWord<'_> {
+    pub fn into_owned(
+        Self {
+            text,
+            start_time,
+            end_time,
+        }: Self,
+    ) -> LyricWordOwned {
+        LyricWordOwned {
+            text: text.into(),
+            start_time,
+            end_time,
+        }
+    }
+}
+
+impl LyricLineOwned {
+    /// whether this line carries word-level timings
+    pub fn is_word_timed(&self) -> bool {
+        !self.words.is_empty()
+    }
+
+    /// whether the line has ended at `elapsed`
+    ///
+    /// a line without `end_time` is considered ended after `max_hold`,
+    /// zero `max_hold` means it never ends by itself
+    pub fn is_over(&self, elapsed: Duration, max_hold: Duration) -> bool {
+        let end_time = self
+            .end_time
+            .or_else(|| (!max_hold.is_zero()).then(|| self.start_time.saturating_add(max_hold)));
+        end_time.is_some_and(|end_time| elapsed >= end_time)
+    }
+}
+
 pub struct LyricStore {
     lyric: Option<String>,
     tlyric: Option<String>,
+    /// romanized lyric, like romaji or pinyin
+    rlyric: Option<String>,
+    /// word-timed lyric in the format of provider, like YRC of Netease
+    wlyric: Option<String>,
 }
 
 #[derive(Debug, thiserror::Error)]
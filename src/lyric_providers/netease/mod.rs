@@ -4,31 +4,98 @@ use std::time::Duration;
 use ncmapi::types::{Album, Artist, Song};
 use ncmapi::NcmApi;
 
-use ncmapi::types::{LyricResp, SearchSongResp};
+use ncmapi::types::SearchSongResp;
+use serde::Deserialize;
+use url::Url;
 
+use crate::log::warn;
 use crate::tokio_spawn;
 
 use super::dict::ARTIST_ALIAS_MAP;
-use super::{default_search_query, Lyric, LyricLineOwned, LyricOwned, LyricStore};
+use super::{default_search_query, Capabilities, Lyric, LyricOwned, LyricStore, HTTP_CLIENT};
+
+pub mod yrc;
+use yrc::parse_yrc;
+
+/// serves YRC besides what ncmapi gets
+const LYRIC_URL: &str = "https://music.163.com/api/song/lyric/v1";
 
 #[derive(Clone, Copy)]
 pub struct Netease;
 
+/// `LyricResp` from ncmapi, with romanized and word-timed lyric
+///
+/// `ytlrc` and `yromalrc` are timed as `yrc`
+#[derive(Debug, Deserialize)]
+struct LyricRespWithRoma {
+    lrc: Option<LyricItem>,
+    tlyric: Option<LyricItem>,
+    romalrc: Option<LyricItem>,
+    yrc: Option<LyricItem>,
+    ytlrc: Option<LyricItem>,
+    yromalrc: Option<LyricItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LyricItem {
+    lyric: String,
+}
+
+fn non_empty(item: Option<LyricItem>) -> Option<String> {
+    item.map(|item| item.lyric)
+        .filter(|lyric| !lyric.trim().is_empty())
+}
+
+async fn query_lyric_v1(id: &str) -> Result<LyricRespWithRoma> {
+    let url = Url::parse_with_params(
+        LYRIC_URL,
+        [
+            ("id", id),
+            ("lv", "-1"),
+            ("tv", "-1"),
+            ("rv", "-1"),
+            ("yv", "-1"),
+            ("ytv", "-1"),
+            ("yrv", "-1"),
+        ],
+    )?;
+    Ok(HTTP_CLIENT
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?)
+}
+
 #[async_trait::async_trait]
 impl super::LyricProvider for Netease {
-    fn init(self, _config: &str) -> Result<()> {
+    fn init(&self, _config: &str) -> Result<()> {
         Ok(())
     }
 
+    fn id(&self) -> &'static str {
+        "netease"
+    }
+
     fn unique_name(&self) -> &'static str {
         "网易云音乐"
     }
 
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            translation: true,
+            word_timing: true,
+            romanization: true,
+        }
+    }
+
     async fn search_song_detailed(
         &self,
         album: &str,
         artists: &[&str],
         title: &str,
+        _length: Option<Duration>,
     ) -> Result<Vec<super::SongInfo>> {
         let artists = artists
             .iter()
@@ -41,17 +108,33 @@ impl super::LyricProvider for Netease {
     async fn query_lyric(&self, id: &str) -> Result<LyricStore> {
         let id = id.to_owned();
         tokio_spawn!(async move {
-            let api = NcmApi::new(false, "");
-            let id = id.parse()?;
-            let query_result = api.lyric(id).await?;
-
-            let lyric_resp: LyricResp = query_result.deserialize()?;
+            let lyric_resp: LyricRespWithRoma = match query_lyric_v1(&id).await {
+                Ok(lyric_resp) => lyric_resp,
+                Err(e) => {
+                    warn!("cannot query word-timed lyric, falling back to line-timed: {e}");
+                    let api = NcmApi::new(false, "");
+                    api.lyric(id.parse()?).await?.deserialize()?
+                }
+            };
 
             crate::log::debug!("lyric query result: {lyric_resp:?}");
 
+            let wlyric = non_empty(lyric_resp.yrc);
+            // translation and romanization timed as YRC are preferred along with it
+            let (tlyric, rlyric) = if wlyric.is_some() {
+                (
+                    non_empty(lyric_resp.ytlrc).or(non_empty(lyric_resp.tlyric)),
+                    non_empty(lyric_resp.yromalrc).or(non_empty(lyric_resp.romalrc)),
+                )
+            } else {
+                (non_empty(lyric_resp.tlyric), non_empty(lyric_resp.romalrc))
+            };
+
             Ok(LyricStore {
                 lyric: lyric_resp.lrc.map(|l| l.lyric),
-                tlyric: lyric_resp.tlyric.map(|l| l.lyric),
+                tlyric,
+                rlyric,
+                wlyric,
             })
         })
         .await?
@@ -108,7 +191,11 @@ impl super::LyricProvider for Netease {
 }
 
 impl super::LyricParse for Netease {
+    /// word-timed YRC, or line-timed LRC if there is no YRC
     fn parse_lyric(&self, store: &LyricStore) -> LyricOwned {
+        if let Some(lyric @ LyricOwned::LineTimestamp(_)) = store.wlyric.as_deref().map(parse_yrc) {
+            return lyric;
+        }
         let lyric = store.lyric.as_deref();
         verify_lyric(lyric).into_owned()
     }
@@ -118,36 +205,30 @@ impl super::LyricParse for Netease {
         let tlyric = store.tlyric.as_deref();
         let mut tlyric = verify_lyric(tlyric).into_owned();
 
-        // workaround: hide translation if original line ends
+        // hide translation if original line ends
         if let (LyricOwned::LineTimestamp(tlyric), LyricOwned::LineTimestamp(olyric)) =
             (&mut tlyric, &olyric)
         {
-            let last_t = tlyric.last();
-            if let Some(next_line_time) = last_t.and_then(|t| {
-                olyric
+            if let Some(last_t) = tlyric.last_mut() {
+                let next_line_time = olyric
                     .iter()
-                    .find(|o| o.start_time > t.start_time)
-                    .map(|o| o.start_time)
-            }) {
-                tlyric.push(LyricLineOwned {
-                    text: String::default(),
-                    start_time: next_line_time,
-                });
+                    .find(|o| o.start_time > last_t.start_time)
+                    .map(|o| o.start_time);
+                last_t.end_time = last_t.end_time.or(next_line_time);
             }
         }
         tlyric
     }
+
+    fn parse_romanized_lyric(&self, store: &LyricStore) -> LyricOwned {
+        let rlyric = store.rlyric.as_deref();
+        verify_lyric(rlyric).into_owned()
+    }
 }
 
 fn verify_lyric(lyric: Option<&str>) -> Lyric<'_> {
     match lyric {
         Some("") | None => super::Lyric::None,
-        Some(lyric) => {
-            if let Ok(parsed) = super::utils::lrc_iter(lyric.lines()) {
-                Lyric::LineTimestamp(parsed)
-            } else {
-                Lyric::NoTimestamp
-            }
-        }
+        Some(lyric) => super::utils::lrc_or_plain(lyric),
     }
 }
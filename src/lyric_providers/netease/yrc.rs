@@ -0,0 +1,78 @@
+//! YRC, word-timed lyric of Netease
+//!
+//! each lyric line is like
+//!
+//! ```text
+//! [1000,2000](1000,500,0)Hello (1500,1500,0)world
+//! ```
+//!
+//! where `[start,duration]` of the line and `(start,duration,0)` of each word
+//! are in milliseconds from the start of the song.
+//! lines of JSON, like `{"t":0,"c":[{"tx":"作词: "}]}`, are credits and ignored.
+
+use std::time::Duration;
+
+use crate::lyric_providers::utils::word_timed_line;
+use crate::lyric_providers::{LyricLineOwned, LyricOwned, LyricWordOwned};
+
+/// parses YRC, lines are sorted by start time
+pub fn parse_yrc(yrc: &str) -> LyricOwned {
+    let mut lines: Vec<_> = yrc.lines().filter_map(parse_yrc_line).collect();
+    if lines.is_empty() {
+        return LyricOwned::None;
+    }
+    lines.sort_by_key(|line| line.start_time);
+    LyricOwned::LineTimestamp(lines)
+}
+
+fn parse_yrc_line(line: &str) -> Option<LyricLineOwned> {
+    let (timing, mut rest) = line.trim().strip_prefix('[')?.split_once(']')?;
+    let (start, duration) = timing.split_once(',')?;
+    let start_time = Duration::from_millis(start.trim().parse().ok()?);
+    let end_time = start_time + Duration::from_millis(duration.trim().parse().ok()?);
+
+    // text before the first word timing is dropped
+    let mut words: Vec<LyricWordOwned> = vec![];
+    while let Some(open) = rest.find('(') {
+        let timing = rest[open + 1..]
+            .split_once(')')
+            .and_then(|(timing, _)| Some((parse_word_timing(timing)?, timing.len())));
+        let Some(((word_start, word_duration), len)) = timing else {
+            // parentheses in text, like `(oh)`
+            if let Some(word) = words.last_mut() {
+                word.text.push_str(&rest[..=open]);
+            }
+            rest = &rest[open + 1..];
+            continue;
+        };
+
+        if let Some(word) = words.last_mut() {
+            word.text.push_str(&rest[..open]);
+        }
+        words.push(LyricWordOwned {
+            text: String::new(),
+            start_time: word_start,
+            end_time: Some(word_start + word_duration),
+        });
+        rest = &rest[open + len + 2..];
+    }
+    if let Some(word) = words.last_mut() {
+        word.text.push_str(rest);
+    }
+
+    Some(word_timed_line(start_time, Some(end_time), words))
+}
+
+/// `start,duration,0`
+fn parse_word_timing(timing: &str) -> Option<(Duration, Duration)> {
+    let mut fields = timing.split(',').map(|field| field.trim().parse::<u64>());
+    let start = fields.next()?.ok()?;
+    let duration = fields.next()?.ok()?;
+    let _ = fields.next()?.ok()?;
+    fields.next().is_none().then(|| {
+        (
+            Duration::from_millis(start),
+            Duration::from_millis(duration),
+        )
+    })
+}
@@ -13,7 +13,7 @@ use url::Url;
 use crate::lyric_providers::{default_search_query, SongInfo};
 use crate::tokio_spawn;
 
-use super::{Lyric, LyricOwned, LyricStore};
+use super::{Capabilities, LyricOwned, LyricStore, HTTP_CLIENT};
 
 mod typo;
 pub use typo::QQMusicConfig;
@@ -23,7 +23,7 @@ pub struct QQMusic;
 
 #[async_trait::async_trait]
 impl super::LyricProvider for QQMusic {
-    fn init(self, config: &str) -> Result<()> {
+    fn init(&self, config: &str) -> Result<()> {
         let QQMusicConfig {
             api_base_url,
             cookies,
@@ -38,8 +38,7 @@ impl super::LyricProvider for QQMusic {
             async fn login_qqmusic(cookies: &str, api: &QQMusicApi) -> Result<()> {
                 let req = api.set_cookie(cookies)?;
                 let reqw_req = reqwest::Request::try_from(req)?;
-                let client = Client::builder().user_agent("Waylyrics/0.1").build()?;
-                client.execute(reqw_req).await?;
+                HTTP_CLIENT.execute(reqw_req).await?;
                 Ok(())
             }
             let Some(cookies) = cookies else { return };
@@ -52,15 +51,32 @@ impl super::LyricProvider for QQMusic {
         Ok(())
     }
 
+    fn id(&self) -> &'static str {
+        "qqmusic"
+    }
+
     fn unique_name(&self) -> &'static str {
         "QQ音乐"
     }
 
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            translation: true,
+            word_timing: false,
+            romanization: true,
+        }
+    }
+
+    fn config_section(&self) -> Option<&'static str> {
+        Some("qqmusic")
+    }
+
     async fn search_song_detailed(
         &self,
         album: &str,
         artists: &[&str],
         title: &str,
+        _length: Option<Duration>,
     ) -> Result<Vec<SongInfo>> {
         let keyword = default_search_query(album, artists, title);
         self.search_song(&keyword).await
@@ -69,8 +85,6 @@ impl super::LyricProvider for QQMusic {
     async fn query_lyric(&self, id: &str) -> Result<LyricStore> {
         let id = id.to_owned();
         tokio_spawn!(async move {
-            let client = Client::builder().user_agent("Waylyrics/0.1").build()?;
-
             // might be a little tricky
             let songid = if id.parse::<usize>().is_ok() {
                 SongId::Songid(&id)
@@ -84,23 +98,32 @@ impl super::LyricProvider for QQMusic {
 
             let mid = match songid {
                 SongId::Songmid(mid) => mid.to_owned(),
-                SongId::Songid(id) => get_songmid(api, &client, id).await?,
+                SongId::Songid(id) => get_songmid(api, &HTTP_CLIENT, id).await?,
             };
 
             let url = api.query_lyric(&mid)?.uri().to_string();
-            let resp: QueryLyricResp =
-                serde_json::from_slice(client.get(url).send().await?.bytes().await?.as_ref())?;
+            let bytes = HTTP_CLIENT.get(url).send().await?.bytes().await?;
+            let resp: QueryLyricResp = serde_json::from_slice(bytes.as_ref())?;
 
             if resp.data.code == -1901 {
                 return Ok(LyricStore {
                     lyric: None,
                     tlyric: None,
+                    rlyric: None,
+                    wlyric: None,
                 });
             }
 
+            // qqmusic-rs does not expose romanized lyric
+            let roma = serde_json::from_slice::<serde_json::Value>(bytes.as_ref())
+                .ok()
+                .and_then(|value| value["data"]["roma"].as_str().map(ToOwned::to_owned));
+
             Ok(LyricStore {
                 lyric: Some(resp.data.lyric),
                 tlyric: Some(resp.data.trans),
+                rlyric: roma,
+                wlyric: None,
             })
         })
         .await?
@@ -111,15 +134,13 @@ impl super::LyricProvider for QQMusic {
         tokio_spawn!(async move {
             crate::log::debug!("search keyword: {keyword}");
 
-            let client = Client::builder().user_agent("Waylyrics/0.1").build()?;
-
             let Some(Some(api)) = QQMUSIC_API_CLIENT.get() else {
                 return Err(Error::ApiClientNotInit)?;
             };
 
             let url = api.search::<Track>(&keyword, None, None)?.uri().to_string();
             let resp: <Track as SearchType>::Resp =
-                serde_json::from_slice(client.get(url).send().await?.bytes().await?.as_ref())?;
+                serde_json::from_slice(HTTP_CLIENT.get(url).send().await?.bytes().await?.as_ref())?;
 
             Ok(resp
                 .data
@@ -168,6 +189,11 @@ impl super::LyricParse for QQMusic {
         let lyric = store.tlyric.as_deref();
         verify_lyric(lyric)
     }
+
+    fn parse_romanized_lyric(&self, store: &LyricStore) -> LyricOwned {
+        let lyric = store.rlyric.as_deref();
+        verify_lyric(lyric)
+    }
 }
 
 fn verify_lyric(lyric: Option<&str>) -> LyricOwned {
@@ -181,11 +207,7 @@ fn verify_lyric(lyric: Option<&str>) -> LyricOwned {
                 .replace("&quot;", "\"")
                 .replace("&apos;", "\'");
 
-            if let Ok(parsed) = super::utils::lrc_iter(lyric.lines()) {
-                Lyric::LineTimestamp(parsed).into_owned()
-            } else {
-                LyricOwned::NoTimestamp
-            }
+            super::utils::lrc_or_plain(&lyric).into_owned()
         }
     }
 }
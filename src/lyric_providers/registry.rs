@@ -0,0 +1,172 @@
+//! lyric providers built in or defined in config, enabled by id in `lyric-search-source`
+//!
+//! a provider with something to configure names its section of config,
+//! which is passed to [`LyricProvider::init`] as JSON once it's enabled.
+
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+use crate::log::{info, warn};
+
+use super::kugou::Kugou;
+use super::library::LyricLibrary;
+use super::lrclib::LRCLib;
+use super::netease::Netease;
+use super::qqmusic::QQMusic;
+use super::LyricProvider;
+
+pub static PROVIDERS: &[&dyn LyricProvider] = &[&Netease, &QQMusic, &LRCLib, &Kugou, &LyricLibrary];
+
+/// providers defined in config, like [`super::http::HttpProvider`]
+/// and [`super::command::CommandProvider`]
+static CUSTOM_PROVIDERS: OnceLock<Vec<&'static dyn LyricProvider>> = OnceLock::new();
+
+/// what a provider may fetch besides original lyric
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Capabilities {
+    pub translation: bool,
+    pub word_timing: bool,
+    pub romanization: bool,
+}
+
+impl Display for Capabilities {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let capabilities: Vec<_> = [
+            (self.translation, "translation"),
+            (self.word_timing, "word timing"),
+            (self.romanization, "romanization"),
+        ]
+        .into_iter()
+        .filter_map(|(supported, name)| supported.then_some(name))
+        .collect();
+
+        if capabilities.is_empty() {
+            f.write_str("lyric only")
+        } else {
+            f.write_str(&capabilities.join(", "))
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum RegistryError {
+    #[error("unknown lyric provider {0:?} in `lyric-search-source`, available: {ids}", ids = available_ids())]
+    Unknown(String),
+    #[error(
+        "invalid lyric provider id {0:?}, only ASCII letters, digits, `-` and `_` are allowed"
+    )]
+    InvalidId(String),
+    #[error("lyric provider id {0:?} is already taken")]
+    DuplicatedId(String),
+    #[error("custom lyric providers are already registered")]
+    Registered,
+    #[error("failed to init lyric provider {id}: {source}")]
+    Init {
+        id: &'static str,
+        source: anyhow::Error,
+    },
+}
+
+/// built-in providers, then those defined in config
+fn all_providers() -> impl Iterator<Item = &'static dyn LyricProvider> {
+    PROVIDERS
+        .iter()
+        .chain(CUSTOM_PROVIDERS.get().into_iter().flatten())
+        .copied()
+}
+
+pub fn is_valid_id(id: &str) -> bool {
+    !id.is_empty()
+        && id
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+}
+
+/// adds providers defined in config, can be called only once before [`setup_providers`]
+///
+/// providers are leaked, as they live until exit
+pub fn register_custom_providers(
+    providers: Vec<Box<dyn LyricProvider>>,
+) -> Result<(), RegistryError> {
+    let mut registered: Vec<&'static dyn LyricProvider> = vec![];
+    for provider in providers {
+        let id = provider.id();
+        if !is_valid_id(id) {
+            return Err(RegistryError::InvalidId(id.to_owned()));
+        }
+        if all_providers()
+            .chain(registered.iter().copied())
+            .any(|other| other.id() == id)
+        {
+            return Err(RegistryError::DuplicatedId(id.to_owned()));
+        }
+        registered.push(Box::leak(provider));
+    }
+    CUSTOM_PROVIDERS
+        .set(registered)
+        .map_err(|_| RegistryError::Registered)
+}
+
+/// comma separated ids of all providers
+pub fn available_ids() -> String {
+    all_providers()
+        .map(|provider| provider.id())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// looks up a provider by its id, or by its display name as older config does
+pub fn get_provider(name: &str) -> Option<&'static dyn LyricProvider> {
+    let name = name.trim();
+    if let Some(provider) = all_providers().find(|provider| provider.id() == name) {
+        return Some(provider);
+    }
+
+    let provider = all_providers().find(|provider| provider.unique_name() == name)?;
+    warn!(
+        "lyric provider {name:?} is deprecated in `lyric-search-source`, use {:?} instead",
+        provider.id()
+    );
+    Some(provider)
+}
+
+/// providers of `names` in order, each initialized with its config section
+///
+/// `sections` is the whole config, in JSON.
+/// unknown names are errors, duplicated ones are ignored
+pub fn setup_providers(
+    names: &[String],
+    sections: &serde_json::Value,
+) -> Result<Vec<&'static dyn LyricProvider>, RegistryError> {
+    let mut providers: Vec<&'static dyn LyricProvider> = vec![];
+    for name in names {
+        let provider = get_provider(name).ok_or_else(|| RegistryError::Unknown(name.clone()))?;
+        if providers
+            .iter()
+            .any(|enabled| enabled.id() == provider.id())
+        {
+            warn!("lyric provider {name:?} is enabled more than once");
+            continue;
+        }
+
+        let config = provider
+            .config_section()
+            .and_then(|section| sections.get(section))
+            .unwrap_or(&serde_json::Value::Null);
+        provider
+            .init(&config.to_string())
+            .map_err(|source| RegistryError::Init {
+                id: provider.id(),
+                source,
+            })?;
+
+        info!(
+            "enabled lyric provider {} ({}): {}",
+            provider.id(),
+            provider.unique_name(),
+            provider.capabilities()
+        );
+        providers.push(provider);
+    }
+    Ok(providers)
+}
@@ -12,11 +12,17 @@
 //!   - The second part consists of 1-2 digits,
 //!   - The millisecond part consists of 1-3 digits.
 //! - The total length (including angle brackets) is between 7 and 11 characters.
+//!
+//! [`split_extended_timestamps`] does the opposite job: it keeps the tags as
+//! word-level timings instead of throwing them away.
 
 use std::borrow::Cow;
+use std::time::Duration;
 
 use memchr::memchr;
 
+use super::LyricWord;
+
 /// Checks whether the given byte slice matches the extended timestamp tag format (e.g., `<mm:ss.ms>`).
 ///
 /// Format requirements:
@@ -82,6 +88,27 @@ fn is_extended_tag(buf: &[u8]) -> bool {
     true
 }
 
+/// Parses an extended timestamp tag (e.g., `<mm:ss.ms>`) into a [`Duration`].
+///
+/// Returns `None` if `buf` is not a valid tag, see [`is_extended_tag`].
+fn parse_extended_tag(buf: &[u8]) -> Option<Duration> {
+    if !is_extended_tag(buf) {
+        return None;
+    }
+
+    // validated above, so it must be `mm:ss.ms` in ASCII
+    let content = std::str::from_utf8(&buf[1..buf.len() - 1]).ok()?;
+    let (min, rest) = content.split_once(':')?;
+    let (sec, subsec) = rest.split_once('.')?;
+
+    let min: u64 = min.parse().ok()?;
+    let sec: u64 = sec.parse().ok()?;
+    // `.5` is 500ms, `.05` is 50ms, `.005` is 5ms
+    let ms = subsec.parse::<u64>().ok()? * 10u64.pow(3 - subsec.len() as u32);
+
+    Some(Duration::from_millis((min * 60 + sec) * 1000 + ms))
+}
+
 /// Finds the range of the next angle-bracket tag starting from the given position.
 ///
 /// This function searches the byte slice for the next tag that starts with `<` and ends with `>`,
@@ -183,3 +210,90 @@ pub fn strip_extended_timestamps<'a>(lrc: &'a str) -> Cow<'a, str> {
         None => Cow::Borrowed(lrc),
     }
 }
+
+/// Splits a lyric line with extended timestamp tags (`<mm:ss.ms>`) into timed words.
+///
+/// Each tag marks the start of the text following it. A tag followed by no text
+/// (e.g., the trailing one) marks the end of the previous word instead.
+/// Text before the first tag starts at `line_start`.
+///
+/// Leading and trailing whitespaces of the line are trimmed, so that
+/// concatenating all word texts gives `strip_extended_timestamps(lrc).trim()`.
+///
+/// # Returns
+/// An empty `Vec` if `lrc` contains no extended timestamp tags.
+pub fn split_extended_timestamps(lrc: &str, line_start: Duration) -> Vec<LyricWord<'_>> {
+    let bytes = lrc.as_bytes();
+
+    // Fast path: if no angle brackets exist, there are no words
+    if memchr(b'<', bytes).is_none() || memchr(b'>', bytes).is_none() {
+        return vec![];
+    }
+
+    // (timestamp, start of the text after the tag, end of the text)
+    let mut segments: Vec<(Duration, usize, usize)> = vec![];
+    let mut current = (line_start, 0);
+    let mut pos = 0;
+    let mut found = false;
+
+    while let Some((tag_start, tag_end)) = find_next_tag(bytes, pos) {
+        pos = tag_end + 1;
+        let Some(time) = parse_extended_tag(&bytes[tag_start..=tag_end]) else {
+            // Retain this tag as a part of the text
+            continue;
+        };
+        found = true;
+        segments.push((current.0, current.1, tag_start));
+        current = (time, pos);
+    }
+    if !found {
+        return vec![];
+    }
+    segments.push((current.0, current.1, lrc.len()));
+
+    // (text, start_time, end_time)
+    let mut words: Vec<(&str, Duration, Option<Duration>)> = vec![];
+    for &(time, start, end) in &segments {
+        if start == end {
+            // no text after this tag, so it is the end of the previous word
+            if let Some((_, _, end_time)) = words.last_mut() {
+                end_time.get_or_insert(time);
+            }
+            continue;
+        }
+        words.push((&lrc[start..end], time, None));
+    }
+
+    // spaces between the line timestamp and the first word
+    while words
+        .first()
+        .is_some_and(|(text, ..)| text.trim_start().is_empty())
+    {
+        words.remove(0);
+    }
+    // spaces after the last word, which mark its end
+    while words
+        .last()
+        .is_some_and(|(text, ..)| text.trim_end().is_empty())
+    {
+        let (_, time, _) = words.pop().unwrap();
+        if let Some((_, _, end_time)) = words.last_mut() {
+            end_time.get_or_insert(time);
+        }
+    }
+    if let Some((text, ..)) = words.first_mut() {
+        *text = text.trim_start();
+    }
+    if let Some((text, ..)) = words.last_mut() {
+        *text = text.trim_end();
+    }
+
+    words
+        .into_iter()
+        .map(|(text, start_time, end_time)| LyricWord {
+            text: Cow::Borrowed(text),
+            start_time,
+            end_time,
+        })
+        .collect()
+}
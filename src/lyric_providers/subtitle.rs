@@ -0,0 +1,227 @@
+//! SubRip (`.srt`) and WebVTT (`.vtt`) subtitles
+//!
+//! each cue becomes a lyric line, multi-line cues are joined with spaces.
+//! WebVTT inline timestamps (`<00:00:01.500>`) are kept as word timings.
+
+use std::fmt::Write;
+use std::time::Duration;
+
+use super::{LyricLineOwned, LyricWordOwned};
+
+/// how long the last cue lasts if the line does not tell
+const LAST_CUE_DURATION: Duration = Duration::from_secs(5);
+
+#[derive(thiserror::Error, Debug)]
+pub enum SubtitleError {
+    #[error("invalid cue timing: {0}")]
+    Timing(String),
+}
+
+/// parses SRT or WebVTT, lines are sorted by start time
+pub fn parse_subtitle(subtitle: &str) -> Result<Vec<LyricLineOwned>, SubtitleError> {
+    let subtitle = subtitle.trim_start_matches('\u{feff}');
+    let mut lines = vec![];
+
+    let mut block = vec![];
+    for line in subtitle.lines().chain([""]) {
+        if !line.trim().is_empty() {
+            block.push(line);
+            continue;
+        }
+        if let Some(line) = parse_cue(&block)? {
+            lines.push(line);
+        }
+        block.clear();
+    }
+
+    lines.sort_by_key(|line| line.start_time);
+    Ok(lines)
+}
+
+/// returns `None` for blocks without timing, like `WEBVTT` header or `NOTE`
+fn parse_cue(block: &[&str]) -> Result<Option<LyricLineOwned>, SubtitleError> {
+    let Some(timing_index) = block.iter().position(|line| line.contains("-->")) else {
+        return Ok(None);
+    };
+    let timing = block[timing_index];
+    let invalid_timing = || SubtitleError::Timing(timing.to_owned());
+
+    let (start, rest) = timing.split_once("-->").ok_or_else(invalid_timing)?;
+    // WebVTT cue settings may follow the end time
+    let end = rest.split_whitespace().next().ok_or_else(invalid_timing)?;
+    let start_time = parse_subtitle_time(start).ok_or_else(invalid_timing)?;
+    let end_time = parse_subtitle_time(end).ok_or_else(invalid_timing)?;
+
+    let text = block[timing_index + 1..]
+        .iter()
+        .map(|line| line.trim())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let (text, words) = parse_cue_text(&text, start_time, end_time);
+
+    Ok(Some(LyricLineOwned {
+        text,
+        start_time,
+        end_time: Some(end_time),
+        words,
+    }))
+}
+
+/// strips tags, and splits words at inline timestamps
+fn parse_cue_text(
+    text: &str,
+    start_time: Duration,
+    end_time: Duration,
+) -> (String, Vec<LyricWordOwned>) {
+    let mut chunks = vec![(start_time, String::new())];
+    let mut rest = text;
+
+    while let Some(index) = rest.find(['<', '{']) {
+        let (before, tag) = rest.split_at(index);
+        chunks.last_mut().unwrap().1 += before;
+
+        let close = if tag.starts_with('<') { '>' } else { '}' };
+        let Some(tag_end) = tag.find(close) else {
+            rest = tag;
+            break;
+        };
+        // `{\an8}` of SRT, or `<i>`, `<v Singer>`, `<00:01.000>` of WebVTT
+        let tag_content = &tag[1..tag_end];
+        if close == '}' && !tag_content.starts_with('\\') {
+            chunks.last_mut().unwrap().1 += &tag[..=tag_end];
+        } else if let Some(time) = parse_subtitle_time(tag_content) {
+            chunks.push((time, String::new()));
+        }
+        rest = &tag[tag_end + 1..];
+    }
+    chunks.last_mut().unwrap().1 += rest;
+
+    let mut words: Vec<_> = chunks
+        .into_iter()
+        .map(|(start_time, text)| LyricWordOwned {
+            text: unescape(&text),
+            start_time,
+            end_time: None,
+        })
+        .filter(|word| !word.text.is_empty())
+        .collect();
+    if let Some(word) = words.first_mut() {
+        word.text = word.text.trim_start().to_owned();
+    }
+    if let Some(word) = words.last_mut() {
+        word.text = word.text.trim_end().to_owned();
+        word.end_time = Some(end_time);
+    }
+    words.retain(|word| !word.text.is_empty());
+
+    let text = words.iter().map(|word| word.text.as_str()).collect();
+    if words.len() == 1 {
+        words.clear();
+    }
+    (text, words)
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&nbsp;", "\u{a0}")
+        .replace("&lrm;", "\u{200e}")
+        .replace("&rlm;", "\u{200f}")
+        .replace("&amp;", "&")
+}
+
+/// parses `hh:mm:ss,mmm` of SRT, or `[hh:]mm:ss.mmm` of WebVTT
+pub fn parse_subtitle_time(time: &str) -> Option<Duration> {
+    let time = time.trim();
+    let (clock, fraction) = time.split_once([',', '.'])?;
+    if fraction.is_empty() || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    // ms, padded or truncated to 3 digits
+    let ms: u64 = format!("{fraction:0<3}")[..3].parse().ok()?;
+
+    let mut secs = 0;
+    let mut parts = 0;
+    for part in clock.split(':') {
+        parts += 1;
+        if parts > 3 || part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        secs = secs * 60 + part.parse::<u64>().ok()?;
+    }
+    if parts < 2 {
+        return None;
+    }
+
+    Some(Duration::from_secs(secs) + Duration::from_millis(ms))
+}
+
+fn format_subtitle_time(time: Duration, separator: char) -> String {
+    let ms = time.as_millis();
+    let (secs, ms) = (ms / 1000, ms % 1000);
+    let (hours, minutes, secs) = (secs / 3600, secs / 60 % 60, secs % 60);
+    format!("{hours:02}:{minutes:02}:{secs:02}{separator}{ms:03}")
+}
+
+/// lines with text, and when they end
+///
+/// a line without `end_time` lasts until the next line starts
+fn cues(lines: &[LyricLineOwned]) -> impl Iterator<Item = (&LyricLineOwned, Duration)> {
+    lines.iter().enumerate().filter_map(|(index, line)| {
+        if line.text.trim().is_empty() {
+            return None;
+        }
+        let end_time = line
+            .end_time
+            .or_else(|| lines.get(index + 1).map(|next| next.start_time))
+            .filter(|&end_time| end_time > line.start_time)
+            .unwrap_or(line.start_time + LAST_CUE_DURATION);
+        Some((line, end_time))
+    })
+}
+
+pub fn make_srt(lines: &[LyricLineOwned]) -> String {
+    let mut output = String::new();
+    for (index, (line, end_time)) in cues(lines).enumerate() {
+        let _ = write!(
+            output,
+            "{}\n{} --> {}\n{}\n\n",
+            index + 1,
+            format_subtitle_time(line.start_time, ','),
+            format_subtitle_time(end_time, ','),
+            line.text
+        );
+    }
+    output
+}
+
+/// word timings are written as inline timestamps
+pub fn make_vtt(lines: &[LyricLineOwned]) -> String {
+    let escape = |text: &str| {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    };
+
+    let mut output = String::from("WEBVTT\n\n");
+    for (line, end_time) in cues(lines) {
+        let _ = writeln!(
+            output,
+            "{} --> {}",
+            format_subtitle_time(line.start_time, '.'),
+            format_subtitle_time(end_time, '.'),
+        );
+        if line.is_word_timed() {
+            for (index, word) in line.words.iter().enumerate() {
+                if index != 0 {
+                    let _ = write!(output, "<{}>", format_subtitle_time(word.start_time, '.'));
+                }
+                output += &escape(&word.text);
+            }
+        } else {
+            output += &escape(&line.text);
+        }
+        output += "\n\n";
+    }
+    output
+}
@@ -0,0 +1,591 @@
+//! Timed Text Markup Language, in the flavor used by Apple Music
+//!
+//! ```xml
+//! <tt xmlns="http://www.w3.org/ns/ttml" xmlns:ttm="http://www.w3.org/ns/ttml#metadata">
+//!   <head><metadata>
+//!     <ttm:agent type="person" xml:id="v1"/>
+//!     <iTunesMetadata xmlns="http://music.apple.com/lyric-ttml-internal">
+//!       <translations><translation xml:lang="zh-Hans">
+//!         <text for="L1">你好世界</text>
+//!       </translation></translations>
+//!     </iTunesMetadata>
+//!   </metadata></head>
+//!   <body><div>
+//!     <p begin="0:01.000" end="0:03.000" itunes:key="L1" ttm:agent="v1">
+//!       <span begin="0:01.000" end="0:02.000">Hello</span>
+//!       <span begin="0:02.000" end="0:03.000">world</span>
+//!       <span ttm:role="x-bg"><span begin="0:02.500" end="0:03.000">(world)</span></span>
+//!     </p>
+//!   </div></body>
+//! </tt>
+//! ```
+//!
+//! `begin`/`end` are taken as absolute time, as Apple Music does.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::time::Duration;
+
+use quick_xml::escape::{escape, resolve_predefined_entity, unescape};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+use super::{LyricLineOwned, LyricOwned, LyricWordOwned};
+
+#[derive(thiserror::Error, Debug)]
+pub enum TtmlError {
+    #[error("malformed xml: {0}")]
+    Xml(#[from] quick_xml::Error),
+    #[error("malformed xml escape: {0}")]
+    Escape(#[from] quick_xml::escape::EscapeError),
+    #[error("invalid utf-8: {0}")]
+    Utf8(#[from] std::str::Utf8Error),
+    #[error("invalid time expression: {0}")]
+    Time(String),
+}
+
+/// parses a TTML document into original lyric and translation
+pub fn parse_ttml(ttml: &str) -> Result<(LyricOwned, LyricOwned), TtmlError> {
+    let mut parser = TtmlParser::default();
+    let mut reader = Reader::from_str(ttml.trim_start_matches('\u{feff}'));
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => parser.start(&e, false)?,
+            Event::Empty(e) => parser.start(&e, true)?,
+            Event::End(e) => parser.end(e.local_name().as_ref()),
+            Event::Text(e) => parser.text(std::str::from_utf8(&e)?),
+            Event::CData(e) => parser.text(std::str::from_utf8(&e)?),
+            Event::GeneralRef(e) => {
+                let name = std::str::from_utf8(&e)?;
+                match resolve_entity(name) {
+                    Some(text) => parser.text(&text),
+                    None => parser.text(&format!("&{name};")),
+                }
+            }
+            Event::Eof => break,
+            _ => (),
+        }
+    }
+
+    Ok(parser.finish())
+}
+
+fn resolve_entity(name: &str) -> Option<String> {
+    if let Some(code) = name.strip_prefix('#') {
+        let code = match code.strip_prefix('x') {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => code.parse().ok()?,
+        };
+        char::from_u32(code).map(String::from)
+    } else {
+        resolve_predefined_entity(name).map(str::to_owned)
+    }
+}
+
+/// parses TTML time expression, like `1:02.345`, `62.345s` or `500ms`
+pub fn parse_ttml_time(time: &str) -> Option<Duration> {
+    let time = time.trim();
+    let from_secs = |secs: f64| {
+        (secs.is_finite() && secs >= 0.).then(|| Duration::from_millis((secs * 1000.).round() as _))
+    };
+
+    for (metric, scale) in [("ms", 0.001), ("h", 3600.), ("m", 60.), ("s", 1.)] {
+        if let Some(value) = time.strip_suffix(metric) {
+            return value.parse::<f64>().ok().and_then(|v| from_secs(v * scale));
+        }
+    }
+
+    let mut secs = 0.;
+    let mut parts = 0;
+    for part in time.split(':') {
+        parts += 1;
+        if parts > 3 {
+            return None;
+        }
+        secs = secs * 60. + part.parse::<f64>().ok()?;
+    }
+    from_secs(secs)
+}
+
+fn format_ttml_time(time: Duration) -> String {
+    let ms = time.as_millis();
+    let (minutes, ms) = (ms / 60_000, ms % 60_000);
+    format!("{minutes}:{:02}.{:03}", ms / 1000, ms % 1000)
+}
+
+/// a piece of text inside `<p>`, timed if it comes from a `<span>` with `begin`
+#[derive(Debug)]
+struct Segment {
+    text: String,
+    time: Option<(Duration, Option<Duration>)>,
+    /// the element which the text belongs to
+    owner: usize,
+}
+
+#[derive(Debug, Default)]
+struct Span {
+    id: usize,
+    time: Option<(Duration, Option<Duration>)>,
+    role: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct Paragraph {
+    id: usize,
+    begin: Option<Duration>,
+    end: Option<Duration>,
+    key: Option<String>,
+    agent: Option<String>,
+    spans: Vec<Span>,
+    main: Vec<Segment>,
+    background: Vec<Segment>,
+    /// `<span ttm:role="x-translation">`
+    translation: String,
+}
+
+#[derive(Debug, Default)]
+struct TranslationText {
+    key: String,
+    text: String,
+    depth: usize,
+}
+
+#[derive(Debug, Default)]
+struct TtmlParser {
+    next_id: usize,
+    /// agent id => agent name
+    agents: Vec<(String, Option<String>)>,
+    in_agent: bool,
+    in_agent_name: bool,
+    /// only the first `<translation>` is used
+    translation_done: bool,
+    in_translation: bool,
+    translation_text: Option<TranslationText>,
+    translations: HashMap<String, String>,
+    paragraph: Option<Paragraph>,
+    lines: Vec<(LyricLineOwned, Option<String>, Option<String>, String)>,
+}
+
+fn attribute(e: &BytesStart, name: &[u8]) -> Result<Option<String>, TtmlError> {
+    for attr in e.attributes().flatten() {
+        if attr.key.as_ref() == name || attr.key.local_name().as_ref() == name {
+            let value = std::str::from_utf8(&attr.value)?;
+            return Ok(Some(unescape(value)?.into_owned()));
+        }
+    }
+    Ok(None)
+}
+
+fn time_attribute(e: &BytesStart, name: &[u8]) -> Result<Option<Duration>, TtmlError> {
+    attribute(e, name)?
+        .map(|time| parse_ttml_time(&time).ok_or(TtmlError::Time(time)))
+        .transpose()
+}
+
+impl TtmlParser {
+    fn start(&mut self, e: &BytesStart, empty: bool) -> Result<(), TtmlError> {
+        self.next_id += 1;
+        let id = self.next_id;
+        let name = e.local_name();
+
+        if let Some(text) = &mut self.translation_text {
+            if !empty {
+                text.depth += 1;
+            }
+            return Ok(());
+        }
+
+        match name.as_ref() {
+            b"agent" => {
+                if let Some(agent_id) = attribute(e, b"xml:id")? {
+                    self.agents.push((agent_id, None));
+                }
+                self.in_agent = !empty;
+            }
+            b"name" if !empty && self.in_agent => self.in_agent_name = true,
+            b"translation" if !empty && !self.translation_done => self.in_translation = true,
+            b"text" if !empty && self.in_translation => {
+                if let Some(key) = attribute(e, b"for")? {
+                    self.translation_text = Some(TranslationText {
+                        key,
+                        ..Default::default()
+                    });
+                }
+            }
+            b"p" if !empty => {
+                self.paragraph = Some(Paragraph {
+                    id,
+                    begin: time_attribute(e, b"begin")?,
+                    end: time_attribute(e, b"end")?,
+                    key: attribute(e, b"itunes:key")?,
+                    agent: attribute(e, b"ttm:agent")?,
+                    ..Default::default()
+                });
+            }
+            b"span" if !empty => {
+                if let Some(paragraph) = &mut self.paragraph {
+                    let time = time_attribute(e, b"begin")?
+                        .map(|begin| Ok::<_, TtmlError>((begin, time_attribute(e, b"end")?)))
+                        .transpose()?;
+                    paragraph.spans.push(Span {
+                        id,
+                        time,
+                        role: attribute(e, b"ttm:role")?,
+                    });
+                }
+            }
+            b"br" => self.text(" "),
+            _ => (),
+        }
+        Ok(())
+    }
+
+    fn end(&mut self, name: &[u8]) {
+        if let Some(text) = &mut self.translation_text {
+            if text.depth > 0 {
+                text.depth -= 1;
+                return;
+            }
+            let TranslationText { key, text, .. } = self.translation_text.take().unwrap();
+            self.translations.insert(key, normalize_whitespace(&text));
+            return;
+        }
+
+        match name {
+            b"agent" => self.in_agent = false,
+            b"name" => self.in_agent_name = false,
+            b"translation" if self.in_translation => {
+                self.in_translation = false;
+                self.translation_done = true;
+            }
+            b"span" => {
+                if let Some(paragraph) = &mut self.paragraph {
+                    paragraph.spans.pop();
+                }
+            }
+            b"p" => {
+                if let Some(paragraph) = self.paragraph.take() {
+                    self.finish_paragraph(paragraph);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        if let Some(translation) = &mut self.translation_text {
+            translation.text += text;
+            return;
+        }
+        if self.in_agent_name {
+            if let Some((_, name)) = self.agents.last_mut() {
+                name.get_or_insert_with(String::new).push_str(text.trim());
+            }
+            return;
+        }
+        let Some(paragraph) = &mut self.paragraph else {
+            return;
+        };
+
+        let role = |role: &str| {
+            paragraph
+                .spans
+                .iter()
+                .any(|span| span.role.as_deref() == Some(role))
+        };
+        let (translation, romanization, background) =
+            (role("x-translation"), role("x-roman"), role("x-bg"));
+        if translation {
+            paragraph.translation += text;
+            return;
+        }
+        // romanization is not supported yet
+        if romanization {
+            return;
+        }
+
+        let (owner, time) = paragraph
+            .spans
+            .last()
+            .map(|span| (span.id, span.time))
+            .unwrap_or((paragraph.id, None));
+        // the innermost timed span decides timing
+        let time = time.or_else(|| paragraph.spans.iter().rev().find_map(|span| span.time));
+
+        let segments = if background {
+            &mut paragraph.background
+        } else {
+            &mut paragraph.main
+        };
+        match segments.last_mut() {
+            // text split by entities
+            Some(last) if last.owner == owner => last.text += text,
+            _ => segments.push(Segment {
+                text: text.to_owned(),
+                time,
+                owner,
+            }),
+        }
+    }
+
+    fn finish_paragraph(&mut self, paragraph: Paragraph) {
+        let (mut text, mut words) = build_words(paragraph.main);
+        let (bg_text, mut bg_words) = build_words(paragraph.background);
+
+        // background vocal follows the main vocal, in parentheses
+        if !bg_text.is_empty() {
+            let bg_text = if bg_text.starts_with('(') {
+                bg_text
+            } else {
+                if let Some(word) = bg_words.first_mut() {
+                    word.text.insert(0, '(');
+                }
+                if let Some(word) = bg_words.last_mut() {
+                    word.text.push(')');
+                }
+                format!("({bg_text})")
+            };
+
+            if !text.is_empty() {
+                text.push(' ');
+                if let Some(word) = words.last_mut() {
+                    word.text.push(' ');
+                }
+            }
+            text += &bg_text;
+            words.append(&mut bg_words);
+            // either vocal is not word timed
+            if words.iter().map(|word| word.text.len()).sum::<usize>() != text.len() {
+                words.clear();
+            }
+        }
+
+        let start_time = paragraph
+            .begin
+            .or_else(|| words.first().map(|word| word.start_time));
+        let Some(start_time) = start_time else {
+            return;
+        };
+        let end_time = words
+            .iter()
+            .filter_map(|word| word.end_time)
+            .chain(paragraph.end)
+            .max();
+
+        self.lines.push((
+            LyricLineOwned {
+                text,
+                start_time,
+                end_time,
+                words,
+            },
+            paragraph.key,
+            paragraph.agent,
+            normalize_whitespace(&paragraph.translation),
+        ));
+    }
+
+    fn finish(self) -> (LyricOwned, LyricOwned) {
+        let named_agent = |agent: &str| {
+            self.agents
+                .iter()
+                .find(|(id, _)| id == agent)
+                .and_then(|(_, name)| name.as_deref())
+        };
+        let duet = self.agents.len() > 1;
+
+        let mut origin = vec![];
+        let mut translation = vec![];
+        let mut last_agent = None;
+        for (mut line, key, agent, inline_translation) in self.lines {
+            let translated = key
+                .as_ref()
+                .and_then(|key| self.translations.get(key))
+                .filter(|text| !text.is_empty())
+                .cloned()
+                .or((!inline_translation.is_empty()).then_some(inline_translation));
+            if let Some(text) = translated {
+                translation.push(LyricLineOwned {
+                    text,
+                    start_time: line.start_time,
+                    end_time: line.end_time,
+                    words: vec![],
+                });
+            }
+
+            // tell who is singing, when it changes
+            if duet && agent != last_agent {
+                if let Some(name) = agent.as_deref().and_then(named_agent) {
+                    let prefix = format!("{name}: ");
+                    line.text.insert_str(0, &prefix);
+                    if let Some(word) = line.words.first_mut() {
+                        word.text.insert_str(0, &prefix);
+                    }
+                }
+            }
+            last_agent = agent;
+
+            origin.push(line);
+        }
+
+        let into_lyric = |mut lines: Vec<LyricLineOwned>| {
+            lines.sort_by_key(|line| line.start_time);
+            if lines.is_empty() {
+                LyricOwned::None
+            } else {
+                LyricOwned::LineTimestamp(lines)
+            }
+        };
+        (into_lyric(origin), into_lyric(translation))
+    }
+}
+
+fn normalize_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// like `normalize_whitespace`, but keeps a space at both ends
+fn collapse_whitespace(text: &str) -> String {
+    let mut collapsed = String::with_capacity(text.len());
+    for c in text.chars() {
+        if !c.is_whitespace() {
+            collapsed.push(c);
+        } else if !collapsed.ends_with(' ') {
+            collapsed.push(' ');
+        }
+    }
+    collapsed
+}
+
+/// joins segments into line text, and timed ones into words
+///
+/// untimed text (mostly spaces between spans) sticks to the word before it,
+/// so that concatenated words equal to line text
+fn build_words(segments: Vec<Segment>) -> (String, Vec<LyricWordOwned>) {
+    let mut words: Vec<LyricWordOwned> = vec![];
+    let mut pending = String::new();
+
+    for Segment { text, time, .. } in segments {
+        let text = collapse_whitespace(&text);
+
+        match time {
+            Some((start_time, end_time)) => {
+                let mut text = std::mem::take(&mut pending) + &text;
+                if words.last().is_some_and(|word| word.text.ends_with(' ')) {
+                    text = text.trim_start().to_owned();
+                }
+                words.push(LyricWordOwned {
+                    text,
+                    start_time,
+                    end_time,
+                });
+            }
+            None => match words.last_mut() {
+                Some(word) if !(word.text.ends_with(' ') && text.starts_with(' ')) => {
+                    word.text += &text
+                }
+                Some(word) => word.text += text.trim_start(),
+                None => pending += &text,
+            },
+        }
+    }
+
+    if let Some(word) = words.first_mut() {
+        word.text = word.text.trim_start().to_owned();
+    }
+    if let Some(word) = words.last_mut() {
+        word.text = word.text.trim_end().to_owned();
+    }
+    words.retain(|word| !word.text.is_empty());
+
+    if words.is_empty() {
+        return (normalize_whitespace(&pending), words);
+    }
+    let text = words.iter().map(|word| word.text.as_str()).collect();
+    (text, words)
+}
+
+/// generates TTML from lines, translation is matched by `start_time`
+pub fn make_ttml(lines: &[LyricLineOwned], translation: &[LyricLineOwned]) -> String {
+    let word_timed = lines.iter().any(LyricLineOwned::is_word_timed);
+    let mut output = String::new();
+
+    let _ = write!(
+        output,
+        concat!(
+            r#"<tt xmlns="http://www.w3.org/ns/ttml""#,
+            r#" xmlns:itunes="http://music.apple.com/lyric-ttml-internal""#,
+            r#" xmlns:ttm="http://www.w3.org/ns/ttml#metadata" itunes:timing="{}">"#,
+        ),
+        if word_timed { "Word" } else { "Line" }
+    );
+    output += r#"<head><metadata><ttm:agent type="person" xml:id="v1"/>"#;
+
+    let translated: Vec<_> = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            translation
+                .iter()
+                .find(|translated| translated.start_time == line.start_time)
+                .map(|translated| (index, translated))
+        })
+        .collect();
+    if !translated.is_empty() {
+        output += r#"<iTunesMetadata xmlns="http://music.apple.com/lyric-ttml-internal">"#;
+        output += r#"<translations><translation type="subtitle">"#;
+        for (index, line) in translated {
+            let _ = write!(
+                output,
+                r#"<text for="L{}">{}</text>"#,
+                index + 1,
+                escape(&line.text)
+            );
+        }
+        output += "</translation></translations></iTunesMetadata>";
+    }
+    output += "</metadata></head>\n<body><div>\n";
+
+    for (index, line) in lines.iter().enumerate() {
+        let _ = write!(
+            output,
+            r#"<p begin="{}""#,
+            format_ttml_time(line.start_time)
+        );
+        let end_time = line
+            .end_time
+            .or_else(|| lines.get(index + 1).map(|next| next.start_time));
+        if let Some(end_time) = end_time {
+            let _ = write!(output, r#" end="{}""#, format_ttml_time(end_time));
+        }
+        let _ = write!(output, r#" itunes:key="L{}" ttm:agent="v1">"#, index + 1);
+
+        if line.is_word_timed() {
+            for (word_index, word) in line.words.iter().enumerate() {
+                let text = word.text.trim_end();
+                let _ = write!(
+                    output,
+                    r#"<span begin="{}""#,
+                    format_ttml_time(word.start_time)
+                );
+                let end_time = word
+                    .end_time
+                    .or_else(|| line.words.get(word_index + 1).map(|next| next.start_time))
+                    .or(end_time);
+                if let Some(end_time) = end_time {
+                    let _ = write!(output, r#" end="{}""#, format_ttml_time(end_time));
+                }
+                let _ = write!(output, ">{}</span>", escape(text));
+                // spaces between words are kept outside of spans
+                output += &word.text[text.len()..];
+            }
+        } else {
+            output += &escape(&line.text);
+        }
+        output += "</p>\n";
+    }
+    output += "</div></body></tt>\n";
+
+    output
+}
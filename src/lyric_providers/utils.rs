@@ -1,9 +1,10 @@
 use lrc_nom::{parse_single, LrcParseError};
+use std::fmt::{Display, Write};
 use std::{borrow::Cow, time::Duration};
 
-use crate::lyric_providers::strip_extended_timestamps;
+use crate::lyric_providers::{split_extended_timestamps, strip_extended_timestamps};
 
-use super::{LyricLine, LyricLineOwned, LyricProvider};
+use super::{Lyric, LyricLine, LyricLineOwned, LyricOwned, LyricWordOwned};
 
 /// parses lrc tags in each line
 ///
@@ -14,6 +15,9 @@ use super::{LyricLine, LyricLineOwned, LyricProvider};
 /// In the case multiple lyrics comes with the same start_time,
 ///
 /// they are not reordered between them
+///
+/// extended timestamps (`<mm:ss.ms>`) are kept as word timings,
+/// if the line has only one line timestamp
 pub fn lrc_iter<'a>(
     lyric_lines: impl Iterator<Item = &'a str>,
 ) -> Result<Vec<LyricLine<'a>>, LrcParseError> {
@@ -25,12 +29,26 @@ pub fn lrc_iter<'a>(
         .filter_map(|lrc_item| match lrc_item {
             lrc_nom::LrcItem::Metadata(_) => None,
             lrc_nom::LrcItem::Lyric(lyric, timestamps) => {
-                Some(timestamps.into_iter().map(|timestamp| LyricLine {
-                    text: match strip_extended_timestamps(lyric) {
-                        Cow::Borrowed(it) => Cow::Borrowed(it.trim()),
-                        Cow::Owned(it) => Cow::Owned(it.trim().to_owned()),
-                    },
-                    start_time: Duration::from_millis(timestamp as _),
+                // word timestamps are absolute, they cannot be shared between
+                // lines like `[00:01.00][00:31.00]...`
+                let word_timed = timestamps.len() == 1;
+                Some(timestamps.into_iter().map(move |timestamp| {
+                    let start_time = Duration::from_millis(timestamp as _);
+                    let words = if word_timed {
+                        split_extended_timestamps(lyric, start_time)
+                    } else {
+                        vec![]
+                    };
+                    LyricLine {
+                        text: match strip_extended_timestamps(lyric) {
+                            Cow::Borrowed(it) => Cow::Borrowed(it.trim()),
+                            Cow::Owned(it) => Cow::Owned(it.trim().to_owned()),
+                        },
+                        start_time,
+                        // the trailing word tag tells when the line ends
+                        end_time: words.last().and_then(|word| word.end_time),
+                        words,
+                    }
                 }))
             }
         })
@@ -46,6 +64,150 @@ pub fn lrc_iter<'a>(
     Ok(lrc_vec)
 }
 
+/// metadata tags of an lrc file, like `[ti:...]`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LrcMetadata {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub length: Option<Duration>,
+    /// value of `[offset:]` in milliseconds
+    ///
+    /// positive value makes lyric show sooner
+    pub offset_ms: i64,
+}
+
+impl LrcMetadata {
+    /// `[offset:]` converted to `lyric_offset_ms` of main window,
+    /// which delays lyric when positive
+    pub fn lyric_offset_ms(&self) -> i64 {
+        -self.offset_ms
+    }
+}
+
+/// same as [`lrc_iter`], also parses metadata tags
+pub fn lrc_iter_with_metadata<'a>(
+    lyric_lines: impl Iterator<Item = &'a str> + Clone,
+) -> Result<(Vec<LyricLine<'a>>, LrcMetadata), LrcParseError> {
+    let metadata = lrc_metadata(lyric_lines.clone());
+    Ok((lrc_iter(lyric_lines)?, metadata))
+}
+
+/// parses `[key:value]` tags, unknown tags and malformed values are ignored
+pub fn lrc_metadata<'a>(lyric_lines: impl Iterator<Item = &'a str>) -> LrcMetadata {
+    let mut metadata = LrcMetadata::default();
+    for (key, value) in lyric_lines.filter_map(parse_metadata_tag) {
+        let value = value.trim();
+        match key.to_ascii_lowercase().as_str() {
+            "ti" => metadata.title = Some(value.to_owned()),
+            "ar" => metadata.artist = Some(value.to_owned()),
+            "al" => metadata.album = Some(value.to_owned()),
+            "length" => metadata.length = parse_lrc_length(value),
+            "offset" => {
+                if let Ok(offset) = value.trim_start_matches('+').parse() {
+                    metadata.offset_ms = offset;
+                }
+            }
+            _ => (),
+        }
+    }
+    metadata
+}
+
+/// `[key:value]` with alphabetic key, timestamps like `[00:12.34]` are not tags
+fn parse_metadata_tag(line: &str) -> Option<(&str, &str)> {
+    let tag = line.trim().strip_prefix('[')?.strip_suffix(']')?;
+    let (key, value) = tag.split_once(':')?;
+    (!key.is_empty() && key.chars().all(|c| c.is_ascii_alphabetic())).then_some((key, value))
+}
+
+/// `mm:ss` or `mm:ss.xx`
+fn parse_lrc_length(length: &str) -> Option<Duration> {
+    let (min, sec) = length.split_once(':')?;
+    let min: u64 = min.trim().parse().ok()?;
+    let sec: f64 = sec.trim().parse().ok()?;
+    (sec.is_finite() && sec >= 0.)
+        .then(|| Duration::from_secs(min * 60) + Duration::from_secs_f64(sec))
+}
+
+/// parses `lyric` with [`lrc_iter`],
+/// falls back to plain text if it has no timestamped line
+///
+/// lines started with '[' are not considered as plain text
+pub fn lrc_or_plain(lyric: &str) -> Lyric<'_> {
+    if let Ok(parsed) = lrc_iter(lyric.lines()) {
+        if !parsed.is_empty() {
+            return Lyric::LineTimestamp(parsed);
+        }
+    }
+
+    let plain = lyric
+        .lines()
+        .filter(|line| !line.starts_with('['))
+        .collect::<Vec<_>>()
+        .join("\n");
+    match plain.trim() {
+        "" => Lyric::None,
+        plain => Lyric::NoTimestamp(Cow::Owned(plain.to_owned())),
+    }
+}
+
+/// [`lrc_or_plain`] of an optional lyric, empty lyric is `None`
+pub fn lrc_or_none(lyric: Option<&str>) -> LyricOwned {
+    match lyric {
+        Some("") | None => LyricOwned::None,
+        Some(lyric) => lrc_or_plain(lyric).into_owned(),
+    }
+}
+
+/// leaks id and name of a provider defined in config, as providers live until exit
+///
+/// empty name falls back to id
+pub fn leak_provider_names(id: &str, name: &str) -> (&'static str, &'static str) {
+    let id: &'static str = id.trim().to_owned().leak();
+    let name: &'static str = match name.trim() {
+        "" => id,
+        name => name.to_owned().leak(),
+    };
+    (id, name)
+}
+
+/// builds a line of timed words, like those of KRC
+///
+/// whitespaces around the line are trimmed from its words,
+/// so concatenated word texts equal to the line text
+pub fn word_timed_line(
+    start_time: Duration,
+    end_time: Option<Duration>,
+    mut words: Vec<LyricWordOwned>,
+) -> LyricLineOwned {
+    while words
+        .first()
+        .is_some_and(|word| word.text.trim_start().is_empty())
+    {
+        words.remove(0);
+    }
+    while words
+        .last()
+        .is_some_and(|word| word.text.trim_end().is_empty())
+    {
+        words.pop();
+    }
+    if let Some(word) = words.first_mut() {
+        word.text = word.text.trim_start().to_owned();
+    }
+    if let Some(word) = words.last_mut() {
+        word.text = word.text.trim_end().to_owned();
+    }
+
+    LyricLineOwned {
+        text: words.iter().map(|word| word.text.as_str()).collect(),
+        start_time,
+        end_time,
+        words,
+    }
+}
+
 pub fn find_next_lyric<'a>(
     elapsed: &Duration,
     lyric: &'a [LyricLineOwned],
@@ -60,12 +222,50 @@ pub fn find_next_lyric<'a>(
         .last()
 }
 
-pub fn get_provider(provider_id: &str) -> Option<&'static dyn LyricProvider> {
-    use super::lrclib::LRCLib;
-    use super::netease::Netease;
-    use super::qqmusic::QQMusic;
-    let providers: [&'static dyn super::LyricProvider; 3] = [&Netease, &QQMusic, &LRCLib];
-    providers
-        .into_iter()
-        .find(|p| p.unique_name() == provider_id)
+pub fn format_lrc_time(time: Duration) -> String {
+    let mut ms = time.as_millis() as u64;
+    let mut sec = ms / 1000;
+    let min = sec / 60;
+    sec %= 60;
+    ms %= 1000;
+
+    format!("{min:02}:{sec:02}.{ms:03}")
+}
+
+pub fn make_lrc_line(text: impl Display, start_time: Duration) -> String {
+    let time = format_lrc_time(start_time);
+    format!("[{time}]{text}")
+}
+
+/// builds enhanced LRC text like `<00:01.000>Hello <00:01.500>world<00:02.000>`
+pub fn make_word_timed_text(words: &[LyricWordOwned]) -> String {
+    let mut output = String::default();
+    for (index, word) in words.iter().enumerate() {
+        let _ = write!(
+            output,
+            "<{}>{}",
+            format_lrc_time(word.start_time),
+            word.text
+        );
+
+        let next_start = words.get(index + 1).map(|w| w.start_time);
+        if let Some(end_time) = word.end_time.filter(|&end| Some(end) != next_start) {
+            let _ = write!(output, "<{}>", format_lrc_time(end_time));
+        }
+    }
+    output
+}
+
+/// lrc lines without metadata tags
+pub fn make_lrc_body(lines: &[LyricLineOwned]) -> String {
+    let mut output = String::default();
+    for line in lines {
+        if line.is_word_timed() {
+            output += &make_lrc_line(make_word_timed_text(&line.words), line.start_time);
+        } else {
+            output += &make_lrc_line(&line.text, line.start_time);
+        }
+        output += "\n";
+    }
+    output
 }
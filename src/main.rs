@@ -18,14 +18,17 @@ use tracing_subscriber::prelude::*;
 use tracing_subscriber::{fmt, Registry};
 
 use waylyrics::app::actions::{
-    register_reload_theme, register_set_display_mode, register_set_label, register_set_lyric_align,
-    register_switch_decoration, register_switch_passthrough, UIAction, UI_ACTION,
+    register_move_unsynced_line, register_reload_theme, register_set_display_mode,
+    register_set_label, register_set_lyric_align, register_switch_decoration,
+    register_switch_passthrough, UIAction, UI_ACTION,
 };
 use waylyrics::app::{self, build_main_window};
 
 use waylyrics::config::{append_comments, Config, Triggers};
-use waylyrics::lyric_providers::qqmusic::QQMusic;
-use waylyrics::lyric_providers::utils::get_provider;
+use waylyrics::lyric_providers::command::CommandProvider;
+use waylyrics::lyric_providers::encoding::{encodings_from_labels, LYRIC_ENCODINGS};
+use waylyrics::lyric_providers::http::HttpProvider;
+use waylyrics::lyric_providers::registry::{register_custom_providers, setup_providers};
 use waylyrics::lyric_providers::LyricProvider;
 
 use waylyrics::sync::lyric::fetch::tricks::EXTRACT_TRANSLATED_LYRIC;
@@ -184,6 +187,8 @@ fn build_ui(app: &Application) -> Result<()> {
     let config: Config = toml_edit::de::from_str(&config)?;
     let config_with_docs = append_comments(&toml::to_string(&config)?)?;
     fs::write(config_path, config_with_docs)?;
+    // config sections of lyric providers
+    let provider_sections = serde_json::to_value(&config)?;
 
     #[cfg_attr(windows, allow(unused))]
     let Config {
@@ -195,11 +200,17 @@ fn build_ui(app: &Application) -> Result<()> {
         enable_filter_regex,
         filter_regexies,
         ref length_toleration,
+        ref max_lyric_stretch,
         triggers,
         lyric_search_source,
         skip_auto_search,
         show_default_text_on_idle,
         show_lyric_on_pause,
+        ref line_max_hold,
+        dim_ended_line,
+        context_lines_before,
+        context_lines_after,
+        unsynced_lyric_mode,
         #[cfg(feature = "tray-icon")]
         show_tray_icon,
         #[cfg(feature = "layer-shell")]
@@ -210,12 +221,27 @@ fn build_ui(app: &Application) -> Result<()> {
         player_identity_blacklist,
         enable_local_lyric,
         extract_translated_lyric,
-        qqmusic,
+        lyric_encodings,
+        #[cfg(feature = "export-lyric")]
+        bilingual_lrc_separator,
+        qqmusic: _,
+        lyric_library: _,
+        http_providers,
+        command_providers,
         color_scheme,
         theme_dark_switch,
     } = config;
 
     LYRIC_SEARCH_SKIP.store(skip_auto_search, Ordering::Release);
+    let mut custom_providers: Vec<Box<dyn LyricProvider>> = vec![];
+    for config in http_providers {
+        custom_providers.push(Box::new(HttpProvider::new(config)));
+    }
+    for config in command_providers {
+        custom_providers.push(Box::new(CommandProvider::new(config)?));
+    }
+    register_custom_providers(custom_providers)?;
+    let _ = LYRIC_PROVIDERS.set(setup_providers(&lyric_search_source, &provider_sections)?);
 
     #[cfg(feature = "tray-icon")]
     if show_tray_icon {
@@ -248,13 +274,21 @@ fn build_ui(app: &Application) -> Result<()> {
 
     let enable_filter_regex = enable_filter_regex && !filter_regexies.is_empty();
     let length_toleration_ms = parse_time(length_toleration)?.as_millis();
+    let max_lyric_stretch = parse_time(max_lyric_stretch)?;
+    let line_max_hold = parse_time(line_max_hold)?;
     let wind = build_main_window(
         app,
         enable_filter_regex,
         cache_lyrics,
         length_toleration_ms,
+        max_lyric_stretch,
         show_default_text_on_idle,
         show_lyric_on_pause,
+        line_max_hold,
+        dim_ended_line,
+        unsynced_lyric_mode,
+        context_lines_before,
+        context_lines_after,
         #[cfg(feature = "layer-shell")]
         layer_shell,
         #[cfg(feature = "layer-shell")]
@@ -267,7 +301,13 @@ fn build_ui(app: &Application) -> Result<()> {
         auto_connect,
     );
     register_lyric_display(ObjectExt::downgrade(&wind), lyric_update_interval);
-    register_actions(app, &wind, triggers);
+    register_actions(
+        app,
+        &wind,
+        triggers,
+        #[cfg(feature = "export-lyric")]
+        &bilingual_lrc_separator,
+    );
 
     #[cfg(feature = "action-event")]
     init_play_action_channel(ObjectExt::downgrade(app));
@@ -278,10 +318,6 @@ fn build_ui(app: &Application) -> Result<()> {
         EXCLUDED_REGEXES.set(RegexSet::new(&filter_regexies)?);
     }
 
-    QQMusic.init(&serde_json::to_string(&qqmusic)?)?;
-
-    setup_providers(lyric_search_source);
-
     #[cfg(target_os = "windows")]
     // * workaround for a GTK4 bug:
     // GTK4 will freeze on close request on windows
@@ -294,6 +330,7 @@ fn build_ui(app: &Application) -> Result<()> {
 
     let _ = ENABLE_LOCAL_LYRIC.set(enable_local_lyric);
     let _ = EXTRACT_TRANSLATED_LYRIC.set(extract_translated_lyric);
+    let _ = LYRIC_ENCODINGS.set(encodings_from_labels(&lyric_encodings));
 
     MAIN_WINDOW.set(Some(wind));
     PLAYER_IDENTITY_BLACKLIST.set(player_identity_blacklist);
@@ -311,7 +348,15 @@ fn register_actions(
         reload_theme,
         search_lyric,
         refetch_lyric,
+        unsynced_next_line,
+        unsynced_prev_line,
+        lyric_offset_increase,
+        lyric_offset_decrease,
+        lyric_offset_reset,
+        sync_now_current_line,
+        sync_now_next_line,
     }: Triggers,
+    #[cfg(feature = "export-lyric")] bilingual_lrc_separator: &str,
 ) {
     register_connect(app);
     register_disconnect(app);
@@ -319,26 +364,30 @@ fn register_actions(
     register_set_display_mode(wind);
     register_switch_decoration(wind, &switch_decoration);
     register_switch_passthrough(wind, &switch_passthrough);
+    register_move_unsynced_line(wind, &unsynced_next_line, &unsynced_prev_line);
     register_set_label(wind);
     register_reload_theme(app, wind, &reload_theme);
     register_search_lyric(app, wind, &search_lyric);
     register_remove_lyric(app, wind);
+    register_edit_lyric(app, wind);
+    register_tap_sync_lyric(app, wind);
+    register_lyric_offset(
+        app,
+        wind,
+        &lyric_offset_increase,
+        &lyric_offset_decrease,
+        &lyric_offset_reset,
+    );
+    register_sync_lyric_now(app, wind, &sync_now_current_line, &sync_now_next_line);
+    register_lyric_stretch(app, wind);
     register_reload_lyric(app);
     register_refetch_lyric(app, wind, &refetch_lyric);
     #[cfg(feature = "import-lyric")]
     register_import_lyric(app, wind);
     #[cfg(feature = "export-lyric")]
-    register_export_lyric(app, wind);
-}
-
-fn setup_providers(providers_enabled: Vec<String>) {
-    let mut providers = vec![];
-    for source in providers_enabled {
-        if let Some(provider) = get_provider(&source) {
-            providers.push(provider);
-        }
-    }
-    let _ = LYRIC_PROVIDERS.set(providers);
+    register_export_lyric(app, wind, bilingual_lrc_separator);
+    #[cfg(feature = "export-lyric")]
+    register_embed_lyric(app, wind);
 }
 
 #[cfg(feature = "mimalloc")]
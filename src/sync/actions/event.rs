@@ -15,6 +15,15 @@ pub enum PlayAction {
     RefetchLyric,
     RemoveLyric,
     SearchLyric,
+    EditLyric,
+    TapSyncLyric,
+    IncreaseLyricOffset,
+    DecreaseLyricOffset,
+    ResetLyricOffset,
+    SyncNowCurrentLine,
+    SyncNowNextLine,
+    StretchLyricBySync,
+    ResetLyricStretch,
     #[cfg(feature = "import-lyric")]
     ImportOriginalLyric,
     #[cfg(feature = "import-lyric")]
@@ -23,6 +32,12 @@ pub enum PlayAction {
     ExportOriginalLyric,
     #[cfg(feature = "export-lyric")]
     ExportTranslatedLyric,
+    #[cfg(feature = "export-lyric")]
+    ExportBilingualLyric,
+    #[cfg(feature = "export-lyric")]
+    ExportBilingualLyricSameLine,
+    #[cfg(feature = "export-lyric")]
+    EmbedLyric,
 }
 
 fn register_play_action(app: WeakRef<Application>) -> Sender<PlayAction> {
@@ -38,6 +53,15 @@ fn register_play_action(app: WeakRef<Application>) -> Sender<PlayAction> {
                 PlayAction::RefetchLyric => ("refetch-lyric", None),
                 PlayAction::RemoveLyric => ("remove-lyric", None),
                 PlayAction::SearchLyric => ("search-lyric", None),
+                PlayAction::EditLyric => ("edit-lyric", None),
+                PlayAction::TapSyncLyric => ("tap-sync-lyric", None),
+                PlayAction::IncreaseLyricOffset => ("lyric-offset-increase", None),
+                PlayAction::DecreaseLyricOffset => ("lyric-offset-decrease", None),
+                PlayAction::ResetLyricOffset => ("lyric-offset-reset", None),
+                PlayAction::SyncNowCurrentLine => ("sync-now-current-line", None),
+                PlayAction::SyncNowNextLine => ("sync-now-next-line", None),
+                PlayAction::StretchLyricBySync => ("lyric-stretch-by-sync", None),
+                PlayAction::ResetLyricStretch => ("lyric-stretch-reset", None),
                 #[cfg(feature = "import-lyric")]
                 PlayAction::ImportOriginalLyric => ("import-lyric", Some(true.to_variant())),
                 #[cfg(feature = "import-lyric")]
@@ -46,6 +70,16 @@ fn register_play_action(app: WeakRef<Application>) -> Sender<PlayAction> {
                 PlayAction::ExportOriginalLyric => ("export-lyric", Some(true.to_variant())),
                 #[cfg(feature = "export-lyric")]
                 PlayAction::ExportTranslatedLyric => ("export-lyric", Some(false.to_variant())),
+                #[cfg(feature = "export-lyric")]
+                PlayAction::ExportBilingualLyric => {
+                    ("export-bilingual-lyric", Some(false.to_variant()))
+                }
+                #[cfg(feature = "export-lyric")]
+                PlayAction::ExportBilingualLyricSameLine => {
+                    ("export-bilingual-lyric", Some(true.to_variant()))
+                }
+                #[cfg(feature = "export-lyric")]
+                PlayAction::EmbedLyric => ("embed-lyric", None),
             };
 
             if let Some(app) = app.upgrade() {
@@ -1,8 +1,8 @@
-use crate::app::search_window;
-use crate::log::{info, warn};
+use crate::app::{editor_window, search_window, tap_sync_window};
+use crate::log::{error, info, warn};
 use crate::sync::lyric::fetch::tricks::LYRIC_TAG_CACHE;
 use crate::sync::LyricState;
-use crate::utils::bind_shortcut;
+use crate::utils::{bind_shortcut, gettext};
 use glib_macros::clone;
 use gtk::gio::SimpleAction;
 use gtk::glib::{self, VariantTy};
@@ -62,6 +62,49 @@ pub fn register_search_lyric(app: &Application, wind: &app::Window, trigger: &st
     bind_shortcut("app.search-lyric", wind, trigger);
 }
 
+pub fn register_edit_lyric(app: &Application, wind: &app::Window) {
+    let action = SimpleAction::new("edit-lyric", None);
+    let cache_lyrics = wind.imp().cache_lyrics.get();
+    action.connect_activate(move |_, _| {
+        let (origin, translation) =
+            LYRIC.with_borrow(|lyric| (lyric.origin.clone(), lyric.translation.clone()));
+        let LyricOwned::LineTimestamp(origin) = origin else {
+            let error_msg = gettext("only synced lyric can be edited!");
+            error!(error_msg);
+            show_dialog(gtk::Window::NONE, &error_msg, gtk::MessageType::Error);
+            return;
+        };
+        let translation = match translation {
+            LyricOwned::LineTimestamp(translation) => translation,
+            _ => vec![],
+        };
+
+        let window = editor_window::Window::new(&origin, &translation, cache_lyrics);
+        window.present();
+    });
+    app.add_action(&action);
+}
+
+pub fn register_tap_sync_lyric(app: &Application, wind: &app::Window) {
+    let action = SimpleAction::new("tap-sync-lyric", None);
+    let cache_lyrics = wind.imp().cache_lyrics.get();
+    action.connect_activate(move |_, _| {
+        let text = LYRIC.with_borrow(|lyric| match &lyric.origin {
+            LyricOwned::NoTimestamp(text) => text.clone(),
+            LyricOwned::LineTimestamp(lines) => lines
+                .iter()
+                .map(|line| line.text.as_str())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            LyricOwned::None => String::new(),
+        });
+
+        let window = tap_sync_window::Window::new(&text, cache_lyrics);
+        window.present();
+    });
+    app.add_action(&action);
+}
+
 /// update lyric, but do not ignore cache
 pub fn register_reload_lyric(app: &Application) {
     let action = SimpleAction::new("reload-lyric", None);
@@ -130,9 +173,11 @@ pub fn register_remove_lyric(app: &Application, wind: &app::Window) {
             // Clear current lyric
             let origin = LyricOwned::LineTimestamp(vec![]);
             let translation = LyricOwned::None;
+            let romanization = LyricOwned::None;
             LYRIC.set(LyricState {
                 origin,
                 translation,
+                romanization,
             });
             let cache_lyrics = window.imp().cache_lyrics.get();
             // Update cache
@@ -147,6 +192,95 @@ pub fn register_remove_lyric(app: &Application, wind: &app::Window) {
     app.add_action(&action);
 }
 
+/// step of `lyric-offset-increase` and `lyric-offset-decrease`
+const LYRIC_OFFSET_STEP_MS: i64 = 100;
+
+pub fn register_lyric_offset(
+    app: &Application,
+    wind: &app::Window,
+    increase_trigger: &str,
+    decrease_trigger: &str,
+    reset_trigger: &str,
+) {
+    for (action_name, trigger, step) in [
+        (
+            "lyric-offset-increase",
+            increase_trigger,
+            Some(LYRIC_OFFSET_STEP_MS),
+        ),
+        (
+            "lyric-offset-decrease",
+            decrease_trigger,
+            Some(-LYRIC_OFFSET_STEP_MS),
+        ),
+        ("lyric-offset-reset", reset_trigger, None),
+    ] {
+        let action = SimpleAction::new(action_name, None);
+        action.connect_activate(clone!(
+            #[weak(rename_to = window)]
+            wind,
+            move |_, _| {
+                let offset = match step {
+                    Some(step) => window.imp().lyric_offset_ms.get() + step,
+                    None => 0,
+                };
+                utils::set_lyric_offset(&window, offset);
+            }
+        ));
+        app.add_action(&action);
+
+        bind_shortcut(format!("app.{action_name}"), wind, trigger);
+    }
+}
+
+/// calibrates lyric offset, when the target line is heard starting
+pub fn register_sync_lyric_now(
+    app: &Application,
+    wind: &app::Window,
+    current_line_trigger: &str,
+    next_line_trigger: &str,
+) {
+    for (action_name, trigger, next_line) in [
+        ("sync-now-current-line", current_line_trigger, false),
+        ("sync-now-next-line", next_line_trigger, true),
+    ] {
+        let action = SimpleAction::new(action_name, None);
+        action.connect_activate(clone!(
+            #[weak(rename_to = window)]
+            wind,
+            move |_, _| {
+                utils::sync_lyric_now(&window, next_line);
+            }
+        ));
+        app.add_action(&action);
+
+        bind_shortcut(format!("app.{action_name}"), wind, trigger);
+    }
+}
+
+/// stretches lyric by the last two synced lines, or resets the stretch
+pub fn register_lyric_stretch(app: &Application, wind: &app::Window) {
+    let action = SimpleAction::new("lyric-stretch-by-sync", None);
+    action.connect_activate(clone!(
+        #[weak(rename_to = window)]
+        wind,
+        move |_, _| {
+            utils::stretch_lyric_by_sync(&window);
+        }
+    ));
+    app.add_action(&action);
+
+    let action = SimpleAction::new("lyric-stretch-reset", None);
+    action.connect_activate(clone!(
+        #[weak(rename_to = window)]
+        wind,
+        move |_, _| {
+            utils::set_lyric_stretch(&window, None);
+        }
+    ));
+    app.add_action(&action);
+}
+
 #[cfg(feature = "import-lyric")]
 pub fn register_import_lyric(app: &Application, wind: &app::Window) {
     use utils::import_lyric;
@@ -167,9 +301,11 @@ pub fn register_import_lyric(app: &Application, wind: &app::Window) {
     app.add_action(&action);
 }
 
+/// also registers `export-bilingual-lyric`, whose parameter tells
+/// whether to put translation in the same line, after `bilingual_separator`
 #[cfg(feature = "export-lyric")]
-pub fn register_export_lyric(app: &Application, wind: &app::Window) {
-    use utils::export_lyric;
+pub fn register_export_lyric(app: &Application, wind: &app::Window, bilingual_separator: &str) {
+    use utils::{export_bilingual_lyric, export_lyric};
 
     let action = SimpleAction::new("export-lyric", Some(VariantTy::BOOLEAN));
     action.connect_activate(clone!(
@@ -185,6 +321,41 @@ pub fn register_export_lyric(app: &Application, wind: &app::Window) {
         }
     ));
     app.add_action(&action);
+
+    let bilingual_separator = bilingual_separator.to_owned();
+    let action = SimpleAction::new("export-bilingual-lyric", Some(VariantTy::BOOLEAN));
+    action.connect_activate(clone!(
+        #[weak(rename_to = window)]
+        wind,
+        move |_, arg| {
+            let arg = arg.cloned();
+            let bilingual_separator = bilingual_separator.clone();
+            glib_spawn!(async move {
+                let Some(arg) = arg else { return };
+                let Some(same_line) = arg.get() else { return };
+                let separator = same_line.then_some(bilingual_separator.as_str());
+                export_bilingual_lyric(&window, separator).await;
+            });
+        }
+    ));
+    app.add_action(&action);
+}
+
+#[cfg(feature = "export-lyric")]
+pub fn register_embed_lyric(app: &Application, wind: &app::Window) {
+    use utils::embed_lyric;
+
+    let action = SimpleAction::new("embed-lyric", None);
+    action.connect_activate(clone!(
+        #[weak(rename_to = window)]
+        wind,
+        move |_, _| {
+            glib_spawn!(async move {
+                embed_lyric(&window).await;
+            });
+        }
+    ));
+    app.add_action(&action);
 }
 
 pub fn register_connect(app: &Application) {
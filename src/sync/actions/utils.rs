@@ -1,5 +1,5 @@
 #![allow(unused)]
-use std::fmt::{Display, Write};
+use std::fmt::Write;
 use std::time::Duration;
 
 use gtk::subclass::prelude::ObjectSubclassIsExt;
@@ -7,9 +7,12 @@ use gtk::subclass::prelude::ObjectSubclassIsExt;
 use crate::app::dialog::show_dialog;
 use crate::app::Window;
 use crate::log::{error, info, warn};
-use crate::lyric_providers::LyricOwned;
+use crate::lyric_providers::utils::make_lrc_body;
+use crate::lyric_providers::{LyricLineOwned, LyricOwned};
 use crate::sync::lyric::cache::update_lyric_cache;
-use crate::sync::{LyricState, TrackState, LYRIC, TRACK_PLAYING_STATE};
+use crate::sync::lyric::edit::shift_line;
+use crate::sync::lyric::stretch::{SyncPoint, TimeStretch};
+use crate::sync::{LyricState, TrackMeta, TrackState, LYRIC, TRACK_PLAYING_STATE};
 use crate::utils::gettext;
 
 pub fn update_cache() {
@@ -20,27 +23,151 @@ pub fn update_cache() {
     });
 }
 
-#[cfg(feature = "export-lyric")]
-pub fn make_lrc_line(text: impl Display, start_time: Duration) -> String {
-    let mut ms = start_time.as_millis() as u64;
-    let mut sec = ms / 1000;
-    let min = sec / 60;
-    sec %= 60;
-    ms %= 1000;
+/// applies `offset_ms` right away, and saves it to cache
+pub fn set_lyric_offset(window: &Window, offset_ms: i64) {
+    let imp = window.imp();
+    let diff = offset_ms - imp.lyric_offset_ms.get();
+    imp.lyric_offset_ms.set(offset_ms);
+
+    // do not wait for next player sync
+    if let Some(start) = imp.lyric_start.get() {
+        let start = if diff.is_negative() {
+            start.checked_sub(Duration::from_millis(diff.unsigned_abs()))
+        } else {
+            start.checked_add(Duration::from_millis(diff as _))
+        };
+        if let Some(start) = start {
+            imp.lyric_start.set(Some(start));
+        }
+    }
+    info!("set offset: {offset_ms}ms");
+
+    if imp.cache_lyrics.get() {
+        update_cache();
+    }
+}
+
+/// calibrates offset, so that the target line starts right now
+///
+/// see [`calibrate_lyric_offset`] for the target line.
+/// the line is also kept as a sync point for [`stretch_lyric_by_sync`]
+pub fn sync_lyric_now(window: &Window, next_line: bool) {
+    let imp = window.imp();
+    let Some(elapsed) = imp.lyric_start.get().and_then(|start| start.elapsed().ok()) else {
+        warn!("cannot sync lyric without a playing track");
+        return;
+    };
+    let offset_ms = imp.lyric_offset_ms.get();
+    let stretch = imp.lyric_stretch.get();
+
+    let offset = LYRIC.with_borrow(|lyric| match (&lyric.origin, stretch) {
+        (LyricOwned::LineTimestamp(lines), None) => {
+            calibrate_lyric_offset(lines, elapsed, offset_ms, next_line)
+        }
+        // calibrate with lines as they are shown
+        (LyricOwned::LineTimestamp(lines), Some(stretch)) => {
+            let mut lines = lines.clone();
+            lines
+                .iter_mut()
+                .for_each(|line| stretch.apply_to_line(line));
+            calibrate_lyric_offset(&lines, elapsed, offset_ms, next_line)
+        }
+        _ => None,
+    });
+    let Some(offset) = offset else {
+        warn!("no line to sync with");
+        return;
+    };
+
+    // position of the track does not change with offset, unlike `elapsed`
+    let position = elapsed.as_millis() as i64 + offset_ms;
+    let shown_at = Duration::from_millis((position - offset).max(0) as u64);
+    add_sync_point(
+        window,
+        SyncPoint {
+            lyric_time: stretch.unwrap_or_default().lyric_time(shown_at),
+            position: Duration::from_millis(position.max(0) as u64),
+        },
+    );
+    set_lyric_offset(window, offset);
+}
 
-    format!("[{min:02}:{sec:02}.{ms:03}]{text}")
+/// keeps the last two points, a line synced again replaces its old point
+fn add_sync_point(window: &Window, point: SyncPoint) {
+    let mut points = window.imp().sync_points.borrow_mut();
+    points.retain(|old| old.lyric_time != point.lyric_time);
+    points.push(point);
+    if points.len() > 2 {
+        points.remove(0);
+    }
+}
+
+/// applies `stretch` right away, and saves it to cache
+pub fn set_lyric_stretch(window: &Window, stretch: Option<TimeStretch>) {
+    let imp = window.imp();
+    let stretch = stretch.filter(|stretch| !stretch.is_identity());
+    imp.lyric_stretch.set(stretch);
+    info!("set stretch: {stretch:?}");
+
+    if imp.cache_lyrics.get() {
+        update_cache();
+    }
+}
+
+/// stretches lyric, so that the last two synced lines start when they were heard
+pub fn stretch_lyric_by_sync(window: &Window) {
+    let imp = window.imp();
+    let points = imp.sync_points.borrow().clone();
+    let [first, second] = points[..] else {
+        let error_msg = gettext("sync a line near the start and another near the end first!");
+        error!(error_msg);
+        show_dialog(gtk::Window::NONE, &error_msg, gtk::MessageType::Error);
+        return;
+    };
+
+    let Some(stretch) = TimeStretch::from_sync_points(first, second, imp.lyric_offset_ms.get())
+    else {
+        let error_msg = gettext("synced lines are too close, or do not fit any stretch!");
+        error!(error_msg);
+        show_dialog(gtk::Window::NONE, &error_msg, gtk::MessageType::Error);
+        return;
+    };
+    set_lyric_stretch(window, Some(stretch));
+}
+
+/// offset which makes the target line start right at `elapsed`
+///
+/// `elapsed` is the lyric position with `offset_ms` applied.
+/// the target is the line being shown, or the one after it if `next_line`,
+/// blank lines are skipped as they cannot be heard
+pub fn calibrate_lyric_offset(
+    lines: &[LyricLineOwned],
+    elapsed: Duration,
+    offset_ms: i64,
+    next_line: bool,
+) -> Option<i64> {
+    let mut lines = lines.iter().filter(|line| !line.text.trim().is_empty());
+    let target = if next_line {
+        lines.find(|line| line.start_time > elapsed)
+    } else {
+        lines.take_while(|line| line.start_time <= elapsed).last()
+    }?;
+    Some(offset_ms + elapsed.as_millis() as i64 - target.start_time.as_millis() as i64)
 }
 
 #[cfg(feature = "export-lyric")]
 pub async fn export_lyric(window: &Window, is_original: bool) {
+    use crate::lyric_providers::subtitle::{make_srt, make_vtt};
+    use crate::lyric_providers::ttml::make_ttml;
+
     info!("spawned export-lyric: original={is_original}");
 
     let meta = TRACK_PLAYING_STATE.with_borrow(|meta| meta.metainfo.clone());
-    let current_lyrics = LYRIC.with_borrow(|l| {
+    let (current_lyrics, translation) = LYRIC.with_borrow(|l| {
         if is_original {
-            l.origin.clone()
+            (l.origin.clone(), l.translation.clone())
         } else {
-            l.translation.clone()
+            (l.translation.clone(), LyricOwned::None)
         }
     });
     let offset = window.imp().lyric_offset_ms.get();
@@ -51,7 +178,214 @@ pub async fn export_lyric(window: &Window, is_original: bool) {
         show_dialog(gtk::Window::NONE, &error_msg, gtk::MessageType::Error);
         return;
     };
+    let lines = stretch_lines(window, lines);
 
+    let Some(lrc_file) = LyricFileFormat::add_filters(rfd::AsyncFileDialog::new())
+        .set_title(gettext("Export a lyrics file"))
+        .save_file()
+        .await
+    else {
+        info!("user canceled selection");
+        return;
+    };
+
+    let output = match LyricFileFormat::from_file_name(&lrc_file.file_name()) {
+        LyricFileFormat::Lrc => make_lrc(&lines, meta, offset),
+        // formats below have no offset tag, apply it to timestamps
+        LyricFileFormat::Ttml => {
+            let translation = match translation {
+                LyricOwned::LineTimestamp(translation) => {
+                    shift_lines(stretch_lines(window, translation), offset)
+                }
+                _ => vec![],
+            };
+            make_ttml(&shift_lines(lines, offset), &translation)
+        }
+        LyricFileFormat::Srt => make_srt(&shift_lines(lines, offset)),
+        LyricFileFormat::WebVtt => make_vtt(&shift_lines(lines, offset)),
+    };
+
+    write_exported_lyric(lrc_file, output).await;
+}
+
+/// exports original and translated lyric as one LRC
+///
+/// see [`merge_bilingual_lines`] for `separator`
+#[cfg(feature = "export-lyric")]
+pub async fn export_bilingual_lyric(window: &Window, separator: Option<&str>) {
+    info!("spawned export-bilingual-lyric: separator={separator:?}");
+
+    let meta = TRACK_PLAYING_STATE.with_borrow(|meta| meta.metainfo.clone());
+    let (origin, translation) = LYRIC.with_borrow(|l| (l.origin.clone(), l.translation.clone()));
+    let offset = window.imp().lyric_offset_ms.get();
+
+    let LyricOwned::LineTimestamp(lines) = origin else {
+        let error_msg = gettext("lyric not exising!");
+        error!(error_msg);
+        show_dialog(gtk::Window::NONE, &error_msg, gtk::MessageType::Error);
+        return;
+    };
+    let LyricOwned::LineTimestamp(translation) = translation else {
+        let error_msg = gettext("translated lyric not existing!");
+        error!(error_msg);
+        show_dialog(gtk::Window::NONE, &error_msg, gtk::MessageType::Error);
+        return;
+    };
+    let (lines, translation) = (
+        stretch_lines(window, lines),
+        stretch_lines(window, translation),
+    );
+
+    let lrc = LyricFileFormat::Lrc;
+    let Some(lrc_file) = rfd::AsyncFileDialog::new()
+        .add_filter(lrc.name(), &[lrc.extension()])
+        .set_title(gettext("Export a lyrics file"))
+        .save_file()
+        .await
+    else {
+        info!("user canceled selection");
+        return;
+    };
+
+    let lines = merge_bilingual_lines(&lines, &translation, separator);
+    write_exported_lyric(lrc_file, make_lrc(&lines, meta, offset)).await;
+}
+
+#[cfg(feature = "export-lyric")]
+async fn write_exported_lyric(file: rfd::FileHandle, output: String) {
+    if let Err(e) = file.write(output.as_bytes()).await {
+        let prompt = gettext("failed to export: ");
+        let error_msg = format!("{prompt}{e}");
+        error!(error_msg);
+        show_dialog(gtk::Window::NONE, &error_msg, gtk::MessageType::Error);
+    }
+}
+
+/// merges translation into original lyric, pairing lines starting at the same time
+///
+/// without `separator`, translation is put right after the original line,
+/// with the same timestamp, which is what `extract_translated_lyric` splits again.
+/// with `separator`, it's appended to the original line, dropping word timings
+#[cfg(feature = "export-lyric")]
+pub fn merge_bilingual_lines(
+    lines: &[LyricLineOwned],
+    translation: &[LyricLineOwned],
+    separator: Option<&str>,
+) -> Vec<LyricLineOwned> {
+    let mut translated_texts = std::collections::HashMap::new();
+    for line in translation {
+        if !line.text.trim().is_empty() {
+            translated_texts
+                .entry(line.start_time)
+                .or_insert(line.text.as_str());
+        }
+    }
+
+    let mut merged = Vec::with_capacity(lines.len() + translated_texts.len());
+    for line in lines {
+        // identical lines would be dropped as translation on reading
+        let translated_text = translated_texts
+            .get(&line.start_time)
+            .filter(|&&text| text != line.text);
+        let Some(&translated_text) = translated_text else {
+            merged.push(line.clone());
+            continue;
+        };
+
+        match separator {
+            Some(separator) => merged.push(LyricLineOwned {
+                text: format!("{}{separator}{translated_text}", line.text),
+                start_time: line.start_time,
+                end_time: line.end_time,
+                words: vec![],
+            }),
+            None => {
+                merged.push(line.clone());
+                merged.push(LyricLineOwned {
+                    text: translated_text.to_owned(),
+                    start_time: line.start_time,
+                    end_time: line.end_time,
+                    words: vec![],
+                });
+            }
+        }
+    }
+    merged
+}
+
+/// lyric file formats supported by import and export
+#[cfg(any(feature = "export-lyric", feature = "import-lyric"))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum LyricFileFormat {
+    Lrc,
+    Ttml,
+    Srt,
+    WebVtt,
+}
+
+#[cfg(any(feature = "export-lyric", feature = "import-lyric"))]
+impl LyricFileFormat {
+    const ALL: [Self; 4] = [Self::Lrc, Self::Ttml, Self::Srt, Self::WebVtt];
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Lrc => "Simple LRC",
+            Self::Ttml => "TTML",
+            Self::Srt => "SubRip",
+            Self::WebVtt => "WebVTT",
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Lrc => "lrc",
+            Self::Ttml => "ttml",
+            Self::Srt => "srt",
+            Self::WebVtt => "vtt",
+        }
+    }
+
+    /// falls back to LRC for unknown extensions
+    fn from_file_name(file_name: &str) -> Self {
+        let extension = std::path::Path::new(file_name)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or_default();
+        Self::ALL
+            .into_iter()
+            .find(|format| format.extension().eq_ignore_ascii_case(extension))
+            .unwrap_or(Self::Lrc)
+    }
+
+    fn add_filters(dialog: rfd::AsyncFileDialog) -> rfd::AsyncFileDialog {
+        Self::ALL.into_iter().fold(dialog, |dialog, format| {
+            dialog.add_filter(format.name(), &[format.extension()])
+        })
+    }
+}
+
+/// moves lines later by `offset_ms`
+#[cfg(feature = "export-lyric")]
+fn shift_lines(mut lines: Vec<LyricLineOwned>, offset_ms: i64) -> Vec<LyricLineOwned> {
+    for line in &mut lines {
+        shift_line(line, offset_ms);
+    }
+    lines
+}
+
+/// bakes time-stretch into timestamps, as lyric files cannot tell it
+#[cfg(feature = "export-lyric")]
+fn stretch_lines(window: &Window, mut lines: Vec<LyricLineOwned>) -> Vec<LyricLineOwned> {
+    if let Some(stretch) = window.imp().lyric_stretch.get() {
+        lines
+            .iter_mut()
+            .for_each(|line| stretch.apply_to_line(line));
+    }
+    lines
+}
+
+#[cfg(feature = "export-lyric")]
+fn make_lrc(lines: &[LyricLineOwned], meta: Option<TrackMeta>, offset: i64) -> String {
     let mut output = String::default();
 
     let _ = output.write_str("[re:waylyrics]\n");
@@ -71,42 +405,92 @@ pub async fn export_lyric(window: &Window, is_original: bool) {
         warn!("metainfo not found! will not generate");
     }
 
-    let _ = output.write_fmt(format_args!("[offset:{offset}]\n"));
+    // `[offset:]` makes lyric show sooner when positive, as opposed to ours
+    let _ = output.write_fmt(format_args!("[offset:{}]\n", -offset));
     let _ = output.write_char('\n');
 
-    for line in lines {
-        output += &make_lrc_line(&line.text, line.start_time);
-        output += "\n";
-    }
+    output + &make_lrc_body(lines)
+}
 
-    let Some(lrc_file) = rfd::AsyncFileDialog::new()
-        .set_title(gettext("Export a lyrics file"))
-        .add_filter("Simple LRC", &["lrc"])
-        .save_file()
-        .await
-    else {
-        info!("user canceled selection");
+#[cfg(feature = "export-lyric")]
+pub async fn embed_lyric(window: &Window) {
+    use crate::app::dialog::ask_dialog;
+    use crate::sync::lyric::embed;
+    use crate::sync::lyric::fetch::tricks::{lyric_tag_exists, LYRIC_TAG_CACHE};
+    use crate::sync::{get_playing_track, is_track_playing, OsImp, OS};
+
+    info!("spawned embed-lyric");
+
+    let track = get_playing_track();
+
+    let Some(music_path) = OS::music_path() else {
+        let error_msg = gettext("only lyric of local file can be embedded!");
+        error!(error_msg);
+        show_dialog(gtk::Window::NONE, &error_msg, gtk::MessageType::Error);
         return;
     };
+    let (origin, translation) = LYRIC.with_borrow(|l| (l.origin.clone(), l.translation.clone()));
+    let LyricOwned::LineTimestamp(lines) = origin else {
+        let error_msg = gettext("lyric not exising!");
+        error!(error_msg);
+        show_dialog(gtk::Window::NONE, &error_msg, gtk::MessageType::Error);
+        return;
+    };
+    let translation = match translation {
+        LyricOwned::LineTimestamp(translation) => translation,
+        _ => vec![],
+    };
+    // tags have no offset, apply it to timestamps
+    let offset = window.imp().lyric_offset_ms.get();
+    let lines = shift_lines(stretch_lines(window, lines), offset);
+    let translation = shift_lines(stretch_lines(window, translation), offset);
 
-    if let Err(e) = lrc_file.write(output.as_bytes()).await {
-        let prompt = gettext("failed to export: ");
+    // the file may be changed by others
+    LYRIC_TAG_CACHE.remove(&music_path);
+    if lyric_tag_exists(&music_path)
+        && !ask_dialog(
+            gtk::Window::NONE,
+            &gettext("The file already has lyrics, overwrite them?"),
+        )
+        .await
+    {
+        info!("user canceled overwriting");
+        return;
+    }
+    // the lyric is of the track playing before asking
+    if !is_track_playing(&track) {
+        let error_msg = gettext("the playing track has changed, lyric is not embedded!");
+        error!(error_msg);
+        show_dialog(gtk::Window::NONE, &error_msg, gtk::MessageType::Error);
+        return;
+    }
+
+    let result = embed::embed_lyric(&music_path, &lines, &translation);
+    LYRIC_TAG_CACHE.remove(&music_path);
+
+    if let Err(e) = result {
+        let prompt = gettext("failed to embed lyric: ");
         let error_msg = format!("{prompt}{e}");
         error!(error_msg);
         show_dialog(gtk::Window::NONE, &error_msg, gtk::MessageType::Error);
+    } else {
+        info!("embedded lyric into {}", music_path.display());
     }
 }
 
 #[cfg(feature = "import-lyric")]
 pub async fn import_lyric(window: &Window, is_original: bool) {
-    use crate::lyric_providers::utils::lrc_iter;
+    use crate::lyric_providers::encoding::decode_local_lyric;
+    use crate::lyric_providers::subtitle::parse_subtitle;
+    use crate::lyric_providers::ttml::parse_ttml;
+    use crate::lyric_providers::utils::{lrc_iter_with_metadata, LrcMetadata};
     use crate::lyric_providers::Lyric;
+    use crate::sync::check_lrc_metadata;
 
     info!("spawned import-lyric: original={is_original}");
 
-    let lrc_file = rfd::AsyncFileDialog::new()
+    let lrc_file = LyricFileFormat::add_filters(rfd::AsyncFileDialog::new())
         .set_title(gettext("Select a lyrics file"))
-        .add_filter("Simple LRC", &["lrc"])
         .pick_file()
         .await;
 
@@ -114,35 +498,81 @@ pub async fn import_lyric(window: &Window, is_original: bool) {
         info!("user canceled selection");
         return;
     };
-    let lrc = match String::from_utf8(lrc_file.read().await) {
+    let bytes = lrc_file.read().await;
+    let lrc = match decode_local_lyric(&bytes) {
         Ok(lrc) => lrc,
         Err(e) => {
-            let prompt = gettext("failed to read LRC in UTF-8: ");
+            let prompt = gettext("failed to decode lyric file: ");
             let error_msg = format!("{prompt}{e}");
             error!(error_msg);
             show_dialog(gtk::Window::NONE, &error_msg, gtk::MessageType::Error);
             return;
         }
     };
-    let lyric = match lrc_iter(lrc.lines()) {
-        Ok(r) => r,
+    let format = LyricFileFormat::from_file_name(&lrc_file.file_name());
+    // TTML may come with translation
+    let parsed = match format {
+        LyricFileFormat::Lrc => lrc_iter_with_metadata(lrc.lines())
+            .map(|(lyric, metadata)| {
+                let lyric = Lyric::LineTimestamp(lyric).into_owned();
+                (lyric, LyricOwned::None, metadata)
+            })
+            .map_err(|e| e.to_string()),
+        LyricFileFormat::Ttml => parse_ttml(&lrc)
+            .map(|(lyric, translation)| (lyric, translation, LrcMetadata::default()))
+            .map_err(|e| e.to_string()),
+        LyricFileFormat::Srt | LyricFileFormat::WebVtt => parse_subtitle(&lrc)
+            .map(|lines| {
+                let lyric = LyricOwned::LineTimestamp(lines);
+                (lyric, LyricOwned::None, LrcMetadata::default())
+            })
+            .map_err(|e| e.to_string()),
+    };
+    let (lyric, ttml_translation, metadata) = match parsed {
+        Ok(parsed) => parsed,
         Err(e) => {
-            let prompt = gettext("input LRC in unsupported format: ");
+            let prompt = match format {
+                LyricFileFormat::Lrc => gettext("input LRC in unsupported format: "),
+                LyricFileFormat::Ttml => gettext("input TTML in unsupported format: "),
+                LyricFileFormat::Srt | LyricFileFormat::WebVtt => {
+                    gettext("input subtitle in unsupported format: ")
+                }
+            };
             let error_msg = format!("{prompt}{e}");
             error!(error_msg);
             show_dialog(gtk::Window::NONE, &error_msg, gtk::MessageType::Error);
             return;
         }
     };
+
+    let track_meta = TRACK_PLAYING_STATE.with_borrow(|state| state.metainfo.clone());
+    if let Some(track_meta) = track_meta {
+        let length_toleration_ms = window.imp().length_toleration_ms.get();
+        if let Err(e) = check_lrc_metadata(&metadata, &track_meta, length_toleration_ms) {
+            let prompt = gettext("lyric file may not match the playing track: ");
+            let warn_msg = format!("{prompt}{e}");
+            warn!(warn_msg);
+            show_dialog(gtk::Window::NONE, &warn_msg, gtk::MessageType::Warning);
+        }
+    }
+    // translation follows the offset of original lyric
+    if is_original {
+        window.imp().lyric_offset_ms.set(metadata.lyric_offset_ms());
+    }
+
     LYRIC.with_borrow_mut(
         |LyricState {
              origin,
              translation,
+             ..
          }| {
             if is_original {
-                *origin = Lyric::LineTimestamp(lyric).into_owned();
+                *origin = lyric;
+                if !ttml_translation.is_none() {
+                    *translation = ttml_translation;
+                }
             } else {
-                *translation = Lyric::LineTimestamp(lyric).into_owned()
+                *translation = lyric
             }
         },
     );
@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use crate::sync::lyric::fetch::LyricHint;
 
 pub(crate) mod common;
@@ -17,6 +19,8 @@ pub trait OsImp {
     fn clean_player();
     fn connect_player_with_id(player_id: impl AsRef<str>);
     fn hint_from_player() -> Option<LyricHint>;
+    /// local file of the playing track, if any
+    fn music_path() -> Option<PathBuf>;
     fn list_players() -> Vec<PlayerId>;
     fn reconnect_player() -> bool;
     /// This function should:
@@ -1,3 +1,4 @@
+use std::path::PathBuf;
 use std::str::FromStr;
 
 use crate::lyric_providers::netease::Netease;
@@ -8,6 +9,23 @@ use crate::sync::lyric::fetch::tricks::{get_lrc_path, lyric_tag_exists};
 use crate::sync::lyric::fetch::LyricHint;
 use crate::sync::ENABLE_LOCAL_LYRIC;
 
+/// local file of the playing track, from `xesam:url`
+pub fn music_path() -> Option<PathBuf> {
+    PLAYER.with_borrow(|player| {
+        let meta = player.as_ref()?.get_metadata().ok()?;
+        file_url_to_path(meta.url()?)
+    })
+}
+
+fn file_url_to_path(url: &str) -> Option<PathBuf> {
+    if !url.starts_with("file://") {
+        return None;
+    }
+    url::Url::from_str(url)
+        .ok()
+        .and_then(|music_uri| music_uri.to_file_path().ok())
+}
+
 pub fn hint_from_player() -> Option<LyricHint> {
     PLAYER.with_borrow(|player| {
         let player = player
@@ -60,9 +78,7 @@ pub fn hint_from_player() -> Option<LyricHint> {
                     {
                         return None;
                     }
-                    let music_path = url::Url::from_str(meta_url)
-                        .ok()
-                        .and_then(|music_uri| music_uri.to_file_path().ok())?;
+                    let music_path = file_url_to_path(meta_url)?;
                     if lyric_tag_exists(&music_path) {
                         return Some(LyricHint::LyricMetadata(music_path));
                     }
@@ -1,10 +1,11 @@
 use std::cell::RefCell;
+use std::path::PathBuf;
 
 mod sync_task;
 use sync_task::{reconnect_player, try_sync_track};
 
 mod hint;
-use hint::hint_from_player;
+use hint::{hint_from_player, music_path};
 
 use mpris::{Metadata, Player, PlayerFinder};
 
@@ -38,6 +39,10 @@ impl super::OsImp for MPRIS {
         hint_from_player()
     }
 
+    fn music_path() -> Option<PathBuf> {
+        music_path()
+    }
+
     fn list_players() -> Vec<PlayerId> {
         find_players()
             .iter()
@@ -72,6 +72,11 @@ impl OsImp for GSMTC {
         None
     }
 
+    /// SMTC does not tell where the track is from
+    fn music_path() -> Option<std::path::PathBuf> {
+        None
+    }
+
     fn list_players() -> Vec<PlayerId> {
         let Some(sessions) = list_sessions() else {
             return vec![];
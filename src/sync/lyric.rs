@@ -1,3 +1,11 @@
 pub mod cache;
+pub mod context;
+pub mod edit;
+#[cfg(feature = "export-lyric")]
+pub mod embed;
 pub mod fetch;
+pub mod karaoke;
 pub mod scroll;
+pub mod stretch;
+pub mod tap;
+pub mod unsynced;
@@ -10,8 +10,9 @@ use serde::{Deserialize, Serialize};
 
 use crate::lyric_providers::LyricOwned;
 use crate::sync::lyric::fetch::fetch_lyric;
+use crate::sync::lyric::stretch::TimeStretch;
 use crate::sync::{LyricState, TrackMeta, LYRIC};
-use crate::{app, CACHE_DIR};
+use crate::{app, CACHE_DIR, MAIN_WINDOW};
 
 /// This will not create cache dir for you -- you should create it yourself.
 ///
@@ -64,7 +65,9 @@ pub async fn fetch_lyric_cached(
                 Ok(LyricCache {
                     olyric: origin,
                     tlyric: translation,
+                    rlyric: romanization,
                     offset,
+                    stretch,
                 }) => {
                     let dbus_conn = GTK_DBUS_CONNECTION
                         .with_borrow(|conn| conn.as_ref().cloned())
@@ -83,9 +86,11 @@ pub async fn fetch_lyric_cached(
                     LYRIC.set(LyricState {
                         origin,
                         translation,
+                        romanization,
                     });
                     window.imp().lyric_offset_ms.set(offset);
-                    info!("set offset: {offset}ms");
+                    window.imp().lyric_stretch.set(stretch);
+                    info!("set offset: {offset}ms, stretch: {stretch:?}");
                     return Ok(());
                 }
                 Err(e) => error!("cache parse error: {e} from {cache_path:?}"),
@@ -113,7 +118,7 @@ pub async fn fetch_lyric_cached(
     result
 }
 
-/// Using olyric and tlyric inside LYRIC to update corresponding cache file.
+/// Using lyrics inside LYRIC, offset and time-stretch of main window to update corresponding cache file.
 pub fn update_lyric_cache(cache_path: &Path) -> bool {
     let cache_dir = cache_path.parent().unwrap();
     if let Err(e) = std::fs::create_dir_all(cache_dir) {
@@ -121,10 +126,21 @@ pub fn update_lyric_cache(cache_path: &Path) -> bool {
         return false;
     }
 
+    let (offset, stretch) = MAIN_WINDOW.with_borrow(|window| {
+        window
+            .as_ref()
+            .map(|window| {
+                let imp = window.imp();
+                (imp.lyric_offset_ms.get(), imp.lyric_stretch.get())
+            })
+            .unwrap_or_default()
+    });
+
     LYRIC.with_borrow(
         |LyricState {
              origin,
              translation,
+             romanization,
          }| {
             // do not cache empty lyric
             if origin.is_none() && translation.is_none() {
@@ -136,7 +152,9 @@ pub fn update_lyric_cache(cache_path: &Path) -> bool {
                 serde_json::to_string(&LyricCache {
                     olyric: origin.clone(),
                     tlyric: translation.clone(),
-                    offset: 0,
+                    rlyric: romanization.clone(),
+                    offset,
+                    stretch,
                 })
                 .expect("cannot serialize lyrics!"),
             ) else {
@@ -154,7 +172,12 @@ pub fn update_lyric_cache(cache_path: &Path) -> bool {
 struct LyricCache {
     olyric: LyricOwned,
     tlyric: LyricOwned,
+    /// caches before romanization support do not have this
+    #[serde(default)]
+    rlyric: LyricOwned,
     offset: i64,
+    #[serde(default)]
+    stretch: Option<TimeStretch>,
 }
 
 fn md5_cache_dir(digest: md5::Digest) -> PathBuf {
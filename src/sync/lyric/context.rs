@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+use crate::lyric_providers::LyricLineOwned;
+
+/// index of the line playing at `elapsed`, same as [`find_next_lyric`]
+///
+/// [`find_next_lyric`]: crate::lyric_providers::utils::find_next_lyric
+pub fn current_line_index(elapsed: &Duration, lyric: &[LyricLineOwned]) -> Option<usize> {
+    lyric
+        .iter()
+        .take_while(|LyricLineOwned { start_time, .. }| start_time <= elapsed)
+        .count()
+        .checked_sub(1)
+}
+
+/// Returns texts of lines around `current`.
+///
+/// `previous[i]` is the (i+1)-th line before current line,
+/// `next[i]` is the (i+1)-th line after it.
+///
+/// If no line started yet, all lines are considered upcoming.
+pub fn context_lines(
+    lyric: &[LyricLineOwned],
+    current: Option<usize>,
+    before: usize,
+    after: usize,
+) -> (Vec<&str>, Vec<&str>) {
+    let text_at = |index: Option<usize>| {
+        index
+            .and_then(|index| lyric.get(index))
+            .map(|line| line.text.as_str())
+            .unwrap_or_default()
+    };
+
+    let previous = (1..=before)
+        .map(|distance| text_at(current.and_then(|current| current.checked_sub(distance))))
+        .collect();
+    let next = (1..=after)
+        .map(|distance| match current {
+            Some(current) => text_at(Some(current + distance)),
+            None => text_at(Some(distance - 1)),
+        })
+        .collect();
+
+    (previous, next)
+}
@@ -0,0 +1,134 @@
+//! lines of the lyric editor, each paired with its translation
+
+use std::time::Duration;
+
+use crate::log::warn;
+use crate::lyric_providers::LyricLineOwned;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EditedLine {
+    pub line: LyricLineOwned,
+    pub translation: Option<LyricLineOwned>,
+}
+
+impl EditedLine {
+    /// moves the line and its translation later by `offset_ms`
+    pub fn shift(&mut self, offset_ms: i64) {
+        shift_line(&mut self.line, offset_ms);
+        if let Some(translation) = &mut self.translation {
+            shift_line(translation, offset_ms);
+        }
+    }
+
+    /// shifts the line to start at `start_time`
+    pub fn set_start_time(&mut self, start_time: Duration) {
+        let offset_ms = start_time.as_millis() as i64 - self.line.start_time.as_millis() as i64;
+        self.shift(offset_ms);
+    }
+
+    /// word timings no longer match once the text changes
+    pub fn set_text(&mut self, text: &str) {
+        set_line_text(&mut self.line, text);
+    }
+
+    /// empty `text` removes the translation
+    pub fn set_translation(&mut self, text: &str) {
+        if text.trim().is_empty() {
+            self.translation = None;
+            return;
+        }
+        let translation = self.translation.get_or_insert_with(|| LyricLineOwned {
+            start_time: self.line.start_time,
+            end_time: self.line.end_time,
+            ..Default::default()
+        });
+        set_line_text(translation, text);
+    }
+}
+
+fn set_line_text(line: &mut LyricLineOwned, text: &str) {
+    if line.text != text {
+        line.text = text.to_owned();
+        line.words.clear();
+    }
+}
+
+/// moves `line` later by `offset_ms`, with its word timings
+pub fn shift_line(line: &mut LyricLineOwned, offset_ms: i64) {
+    let shift = |time: &mut Duration| {
+        let offset = Duration::from_millis(offset_ms.unsigned_abs());
+        *time = if offset_ms.is_negative() {
+            time.saturating_sub(offset)
+        } else {
+            *time + offset
+        };
+    };
+    shift(&mut line.start_time);
+    if let Some(end_time) = &mut line.end_time {
+        shift(end_time);
+    }
+    for word in &mut line.words {
+        shift(&mut word.start_time);
+        if let Some(end_time) = &mut word.end_time {
+            shift(end_time);
+        }
+    }
+}
+
+/// pairs each translated line with the original line shown along with it
+///
+/// translation left without a line is dropped
+pub fn pair_lines(origin: &[LyricLineOwned], translation: &[LyricLineOwned]) -> Vec<EditedLine> {
+    let mut lines: Vec<_> = origin
+        .iter()
+        .map(|line| EditedLine {
+            line: line.clone(),
+            translation: None,
+        })
+        .collect();
+
+    for translated in translation {
+        let index = lines.partition_point(|line| line.line.start_time <= translated.start_time);
+        match index.checked_sub(1).map(|index| &mut lines[index]) {
+            Some(line) if line.translation.is_none() => {
+                line.translation = Some(translated.clone());
+            }
+            _ => warn!("dropped translation without a line: {}", translated.text),
+        }
+    }
+    lines
+}
+
+/// splits edited lines into original lyric and translation, both sorted
+pub fn unpair_lines(lines: Vec<EditedLine>) -> (Vec<LyricLineOwned>, Vec<LyricLineOwned>) {
+    let (mut origin, translation): (Vec<_>, Vec<_>) = lines
+        .into_iter()
+        .map(|EditedLine { line, translation }| (line, translation))
+        .unzip();
+    let mut translation: Vec<_> = translation.into_iter().flatten().collect();
+
+    origin.sort_by_key(|line| line.start_time);
+    translation.sort_by_key(|line| line.start_time);
+    (origin, translation)
+}
+
+/// parses `mm:ss`, `mm:ss.xx` or `mm:ss.xxx`
+pub fn parse_timestamp(time: &str) -> Option<Duration> {
+    let (minutes, seconds) = time.trim().split_once(':')?;
+    let (seconds, fraction) = seconds.split_once('.').unwrap_or((seconds, "0"));
+
+    let is_number = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    if !is_number(minutes) || !is_number(seconds) || !is_number(fraction) || fraction.len() > 3 {
+        return None;
+    }
+    let seconds: u64 = seconds.parse().ok()?;
+    if seconds >= 60 {
+        return None;
+    }
+    let ms: u64 = format!("{fraction:0<3}").parse().ok()?;
+
+    Some(
+        Duration::from_secs(minutes.parse::<u64>().ok()? * 60 + seconds)
+            + Duration::from_millis(ms),
+    )
+}
@@ -0,0 +1,98 @@
+//! writes lyric into tags of local music files
+
+use std::fs::File;
+use std::path::Path;
+
+use lofty::config::{ParseOptions, WriteOptions};
+use lofty::file::{AudioFile, FileType, TaggedFileExt};
+use lofty::id3::v2::{BinaryFrame, Frame, FrameId, Id3v2Tag, UnsynchronizedTextFrame};
+use lofty::mpeg::MpegFile;
+use lofty::tag::{ItemKey, Tag, TagExt};
+use lofty::TextEncoding;
+
+use crate::lyric_providers::id3::{id3_language_codes, make_sylt};
+use crate::lyric_providers::utils::make_lrc_body;
+use crate::lyric_providers::LyricLineOwned;
+
+/// `XXX` means unknown language in ID3
+const UNKNOWN_LANGUAGE: [u8; 3] = *b"XXX";
+
+#[derive(thiserror::Error, Debug)]
+pub enum EmbedError {
+    #[error("cannot open music file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("cannot write tags: {0}")]
+    Lofty(#[from] lofty::error::LoftyError),
+}
+
+/// writes USLT and SYLT frames for ID3v2, or lyrics item for other tags
+///
+/// translation is written only to ID3v2, as a USLT frame in the system language,
+/// other tags cannot tell lyrics in different languages apart.
+pub fn embed_lyric(
+    music_path: &Path,
+    lyric: &[LyricLineOwned],
+    translation: &[LyricLineOwned],
+) -> Result<(), EmbedError> {
+    let tagged_file = lofty::read_from_path(music_path)?;
+    if tagged_file.file_type() == FileType::Mpeg {
+        return embed_id3v2(music_path, lyric, translation);
+    }
+
+    // LYRICS of Vorbis comments, ©lyr of MP4 ilst, etc.
+    let mut tag = tagged_file
+        .primary_tag()
+        .cloned()
+        .unwrap_or_else(|| Tag::new(tagged_file.primary_tag_type()));
+    tag.insert_text(ItemKey::Lyrics, make_lrc_body(lyric));
+    tag.save_to_path(music_path, WriteOptions::default())?;
+    Ok(())
+}
+
+fn embed_id3v2(
+    music_path: &Path,
+    lyric: &[LyricLineOwned],
+    translation: &[LyricLineOwned],
+) -> Result<(), EmbedError> {
+    let mpeg = MpegFile::read_from(&mut File::open(music_path)?, ParseOptions::new())?;
+    let mut tag = mpeg.id3v2().cloned().unwrap_or_else(Id3v2Tag::new);
+    tag.retain(|frame| !matches!(frame.id().as_str(), "USLT" | "SYLT"));
+
+    tag.insert(Frame::UnsynchronizedText(UnsynchronizedTextFrame::new(
+        TextEncoding::UTF8,
+        UNKNOWN_LANGUAGE,
+        String::new(),
+        make_lrc_body(lyric),
+    )));
+    tag.insert(Frame::Binary(BinaryFrame::new(
+        FrameId::new("SYLT")?,
+        make_sylt(lyric, UNKNOWN_LANGUAGE),
+    )));
+
+    if !translation.is_empty() {
+        tag.insert(Frame::UnsynchronizedText(UnsynchronizedTextFrame::new(
+            TextEncoding::UTF8,
+            translation_language(),
+            "translation".into(),
+            make_lrc_body(translation),
+        )));
+    }
+
+    tag.save_to_path(music_path, WriteOptions::default())?;
+    Ok(())
+}
+
+/// system language, which is how a translation frame is picked when reading
+fn translation_language() -> [u8; 3] {
+    #[cfg(feature = "i18n-local-lyric")]
+    let locale = sys_locale::get_locale();
+    #[cfg(not(feature = "i18n-local-lyric"))]
+    let locale: Option<String> = None;
+
+    locale
+        .as_deref()
+        .map(id3_language_codes)
+        .and_then(|codes| codes.first())
+        .and_then(|code| code.as_bytes().try_into().ok())
+        .unwrap_or(UNKNOWN_LANGUAGE)
+}
@@ -4,16 +4,18 @@ use anyhow::Result;
 use std::borrow::Cow;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::task::JoinSet;
 
-use crate::log::{debug, error, info};
+use crate::log::{debug, error, info, warn};
 use gtk::subclass::prelude::ObjectSubclassIsExt;
 
 use crate::lyric_providers::LyricOwned;
 use crate::sync::{LyricState, TrackMeta, LYRIC};
 use crate::{app, tokio_spawn, LYRIC_PROVIDERS, LYRIC_SEARCH_SKIP};
 
-use crate::sync::utils::{self, match_likely_lyric};
+use crate::sync::lyric::stretch::TimeStretch;
+use crate::sync::utils::{self, check_lrc_metadata, match_likely_lyric};
 
 pub(crate) use tricks::LyricHint;
 
@@ -32,11 +34,19 @@ pub async fn fetch_lyric(track_meta: &TrackMeta, window: &app::Window) -> Result
         .map(|s| Cow::Owned(s.join(",")))
         .unwrap_or_else(|| Cow::Borrowed("Unknown"));
 
-    if let Some(LyricHintResult::Lyric { olyric, tlyric }) =
-        tricks::get_lyric_hint_from_player().await
+    if let Some(LyricHintResult::Lyric {
+        olyric,
+        tlyric,
+        metadata,
+    }) = tricks::get_lyric_hint_from_player().await
     {
         info!("fetched lyrics by player hint");
-        set_lyric(olyric, tlyric, &title, &artists_str);
+        let length_toleration_ms = window.imp().length_toleration_ms.get();
+        if let Err(e) = check_lrc_metadata(&metadata, track_meta, length_toleration_ms) {
+            warn!("lyric from player hint may not match {artists_str} - {title}: {e}");
+        }
+        window.imp().lyric_offset_ms.set(metadata.lyric_offset_ms());
+        set_lyric(olyric, tlyric, LyricOwned::None, &title, &artists_str);
         return Ok(());
     }
 
@@ -74,7 +84,7 @@ pub async fn fetch_lyric(track_meta: &TrackMeta, window: &app::Window) -> Result
                     Some(artists.join(","))
                 };
                 let search_result = provider
-                    .search_song_detailed(album.unwrap_or_default(), &artists, title)
+                    .search_song_detailed(album.unwrap_or_default(), &artists, title, length)
                     .await;
                 search_result.map(|songs| {
                     match_likely_lyric(
@@ -85,7 +95,7 @@ pub async fn fetch_lyric(track_meta: &TrackMeta, window: &app::Window) -> Result
                         &songs,
                         length_toleration_ms,
                     )
-                    .map(|(id, weight)| (id.to_owned(), weight, idx))
+                    .map(|(song, weight)| (song.id.clone(), weight, idx, song.length))
                 })
             });
         }
@@ -94,10 +104,10 @@ pub async fn fetch_lyric(track_meta: &TrackMeta, window: &app::Window) -> Result
         let title = title.clone();
         let mut results = vec![];
         while let Some(Ok(re)) = set.join_next().await {
-            let Ok(Some((id, weight, idx))) = re else {
+            let Ok(Some(result)) = re else {
                 continue;
             };
-            results.push((id, weight, idx));
+            results.push(result);
         }
         (results, artists_str, title)
     })
@@ -112,20 +122,26 @@ pub async fn fetch_lyric(track_meta: &TrackMeta, window: &app::Window) -> Result
         .get()
         .expect("lyric providers should be initialized");
 
-    results.sort_by_key(|(_, _, weight)| *weight);
+    results.sort_by_key(|(_, _, weight, _)| *weight);
 
-    for (song_id, weight, platform_idx) in results {
+    for (song_id, weight, platform_idx, song_length) in results {
         let provider = &providers[platform_idx];
         match provider.query_lyric(&song_id).await {
             Ok(lyric) => {
                 let olyric = provider.parse_lyric(&lyric);
                 let tlyric = provider.parse_translated_lyric(&lyric);
+                let rlyric = provider.parse_romanized_lyric(&lyric);
+                let metadata = provider.parse_lyric_metadata(&lyric);
 
                 info!(
                     "fetched {song_id} from {} with weight {weight}",
                     provider.unique_name()
                 );
-                set_lyric(olyric, tlyric, &title, &artists_str);
+                window.imp().lyric_offset_ms.set(metadata.lyric_offset_ms());
+                set_lyric(olyric, tlyric, rlyric, &title, &artists_str);
+                if let Some(length) = length {
+                    fit_track_length(window, song_length, length);
+                }
                 return Ok(());
             }
             Err(e) => {
@@ -139,13 +155,35 @@ pub async fn fetch_lyric(track_meta: &TrackMeta, window: &app::Window) -> Result
     Err(crate::lyric_providers::Error::NoResult)?
 }
 
-fn set_lyric(origin: LyricOwned, translation: LyricOwned, title: &str, artists: &str) {
+/// stretches lyric of the song found, if it's a bit longer or shorter than the track
+fn fit_track_length(window: &app::Window, song_length: Duration, track_length: Duration) {
+    let max_diff = window.imp().max_lyric_stretch.get();
+    if let Some(stretch) = TimeStretch::fit_length(song_length, track_length, max_diff) {
+        info!(
+            "stretch lyric by {:.4} from {song_length:?} to {track_length:?}",
+            stretch.scale
+        );
+        window.imp().lyric_stretch.set(Some(stretch));
+    }
+}
+
+fn set_lyric(
+    origin: LyricOwned,
+    translation: LyricOwned,
+    romanization: LyricOwned,
+    title: &str,
+    artists: &str,
+) {
     debug!("original lyric: {origin:?}");
     debug!("translated lyric: {translation:?}");
+    debug!("romanized lyric: {romanization:?}");
 
     // show info to user if original lyric is empty or no timestamp
     match &origin {
         LyricOwned::LineTimestamp(_) => (),
+        LyricOwned::NoTimestamp(_) => {
+            info!("Only unsynced lyric for {} - {title}", artists,);
+        }
         _ => {
             info!("No lyric for {} - {title}", artists,);
         }
@@ -157,5 +195,6 @@ fn set_lyric(origin: LyricOwned, translation: LyricOwned, title: &str, artists:
     LYRIC.set(LyricState {
         origin,
         translation,
+        romanization,
     });
 }
@@ -1,14 +1,23 @@
+use std::borrow::Cow;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::{LazyLock, OnceLock};
+use std::time::Duration;
 
 use dashmap::DashMap;
-use lofty::file::TaggedFileExt;
+use lofty::config::ParseOptions;
+use lofty::file::{AudioFile, TaggedFileExt};
+use lofty::id3::v2::{Frame, SyncTextContentType, SynchronizedTextFrame, TimestampFormat};
+use lofty::mpeg::MpegFile;
 use lofty::read_from_path;
 use lofty::tag::ItemKey;
 
 use crate::log::{debug, error, warn};
-use crate::lyric_providers::{Lyric, LyricOwned, LyricProvider};
+use crate::lyric_providers::encoding::decode_local_lyric;
+use crate::lyric_providers::id3::{mpeg_frames_to_duration, select_by_language, sylt_lyric};
+use crate::lyric_providers::ttml::parse_ttml;
+use crate::lyric_providers::utils::{lrc_metadata, LrcMetadata};
+use crate::lyric_providers::{LyricOwned, LyricProvider};
 use crate::sync::interop::{OsImp, OS};
 use crate::sync::TrackMeta;
 use crate::LYRIC_PROVIDERS;
@@ -33,6 +42,8 @@ pub enum LyricHintResult {
     Lyric {
         olyric: LyricOwned,
         tlyric: LyricOwned,
+        /// tags of local lrc, default for online lyric
+        metadata: LrcMetadata,
     },
 }
 
@@ -43,11 +54,11 @@ pub async fn get_lyric_hint_from_player() -> Option<LyricHintResult> {
 
     match hint_from_player {
         Some(LyricHint::SongId { song_id, provider }) => {
-            if !LYRIC_PROVIDERS.get().iter().any(|&providers| {
-                providers
-                    .iter()
-                    .any(|pro| pro.unique_name() == provider.unique_name())
-            }) {
+            if !LYRIC_PROVIDERS
+                .get()
+                .iter()
+                .any(|&providers| providers.iter().any(|pro| pro.id() == provider.id()))
+            {
                 warn!(
                     "provider {} suggested by hint is not configured, skipping SongId hint",
                     provider.unique_name()
@@ -60,32 +71,57 @@ pub async fn get_lyric_hint_from_player() -> Option<LyricHintResult> {
             let lyric = provider.query_lyric(&song_id).await.ok()?;
             let olyric = provider.parse_lyric(&lyric);
             let tlyric = provider.parse_translated_lyric(&lyric);
+            let metadata = provider.parse_lyric_metadata(&lyric);
 
-            Some(LyricHintResult::Lyric { olyric, tlyric })
+            Some(LyricHintResult::Lyric {
+                olyric,
+                tlyric,
+                metadata,
+            })
         }
         Some(LyricHint::LyricFile(path)) => {
-            let (olyric, tlyric) = load_local_lyric(&path)?;
-            Some(LyricHintResult::Lyric { olyric, tlyric })
+            let (olyric, tlyric, metadata) = load_local_lyric(&path)?;
+            Some(LyricHintResult::Lyric {
+                olyric,
+                tlyric,
+                metadata,
+            })
         }
         Some(LyricHint::LyricMetadata(path)) => {
-            let (olyric, tlyric) = get_lrc_from_music_metadata(&path)?;
-            Some(LyricHintResult::Lyric { olyric, tlyric })
+            let (olyric, tlyric, metadata) = get_lrc_from_music_metadata(&path)?;
+            Some(LyricHintResult::Lyric {
+                olyric,
+                tlyric,
+                metadata,
+            })
         }
         _ => None,
     }
 }
 
-/// replace file extension with .lrc
+/// extensions of sidecar lyric files, in order of preference
+pub const LOCAL_LYRIC_EXTENSIONS: &[&str] = &["lrc", "ttml"];
+
+/// replace file extension with that of the first existing sidecar lyric file,
+/// or with .lrc if there is none
 ///
 /// `music_path` should be valid file if it's not empty
 ///
-pub fn get_lrc_path(mut music_path: PathBuf) -> Option<PathBuf> {
-    if music_path.set_extension("lrc") {
-        Some(music_path)
-    } else {
-        None
+pub fn get_lrc_path(music_path: PathBuf) -> Option<PathBuf> {
+    let mut lrc_path = None;
+    for extension in LOCAL_LYRIC_EXTENSIONS {
+        let mut lyric_path = music_path.clone();
+        if !lyric_path.set_extension(extension) {
+            return None;
+        }
+        if lyric_path.exists() {
+            return Some(lyric_path);
+        }
+        lrc_path.get_or_insert(lyric_path);
     }
+    lrc_path
 }
+
 // Static cache for lyric tag existence checks
 pub static LYRIC_TAG_CACHE: LazyLock<DashMap<PathBuf, bool>> = LazyLock::new(DashMap::new);
 
@@ -95,18 +131,35 @@ pub fn lyric_tag_exists(music_path: &Path) -> bool {
         return *result;
     }
 
-    let result = read_from_path(music_path)
-        .map_err(|e| warn!("cannot read music file: {e}"))
-        .ok()
-        .as_ref()
-        .and_then(|tagged_file| tagged_file.primary_tag())
-        .and_then(|tag| tag.get(ItemKey::Lyrics))
-        .is_some();
+    // SYLT is not read as `ItemKey::Lyrics`, so frames of MP3 are checked instead
+    let result = if is_mp3(music_path) {
+        read_mpeg(music_path)
+            .as_ref()
+            .and_then(MpegFile::id3v2)
+            .is_some_and(|tag| {
+                tag.into_iter()
+                    .any(|frame| matches!(frame.id().as_str(), "SYLT" | "USLT"))
+            })
+    } else {
+        read_from_path(music_path)
+            .map_err(|e| warn!("cannot read music file: {e}"))
+            .ok()
+            .as_ref()
+            .and_then(|tagged_file| tagged_file.primary_tag())
+            .and_then(|tag| tag.get(ItemKey::Lyrics))
+            .is_some()
+    };
     // Store result in cache
     LYRIC_TAG_CACHE.insert(music_path.to_owned(), result);
     result
 }
-pub fn get_lrc_from_music_metadata(music_path: &Path) -> Option<(LyricOwned, LyricOwned)> {
+pub fn get_lrc_from_music_metadata(
+    music_path: &Path,
+) -> Option<(LyricOwned, LyricOwned, LrcMetadata)> {
+    if let Some(lyric) = get_lrc_from_id3v2(music_path) {
+        return Some(lyric);
+    }
+
     read_from_path(music_path)
         .map_err(|e| error!("cannot read music file: {e}"))
         .ok()
@@ -116,16 +169,121 @@ pub fn get_lrc_from_music_metadata(music_path: &Path) -> Option<(LyricOwned, Lyr
         .and_then(parse_local_lyric)
 }
 
+/// reads SYLT frames, or USLT frames in multiple languages, of an MP3 file
+///
+/// returns `None` if there are neither, a single USLT is read as `ItemKey::Lyrics`
+fn get_lrc_from_id3v2(music_path: &Path) -> Option<(LyricOwned, LyricOwned, LrcMetadata)> {
+    if !is_mp3(music_path) {
+        return None;
+    }
+
+    let mpeg = read_mpeg(music_path)?;
+    let sample_rate = mpeg.properties().sample_rate();
+    let tag = mpeg.id3v2()?;
+
+    let language = |language: &[u8]| String::from_utf8_lossy(language).into_owned();
+    let mut synced = vec![];
+    let mut unsynced = vec![];
+    for frame in tag {
+        match frame {
+            Frame::Binary(binary) if frame.id().as_str() == "SYLT" => {
+                let sylt = match SynchronizedTextFrame::parse(&binary.data[..], frame.flags()) {
+                    Ok(sylt) => sylt,
+                    Err(e) => {
+                        warn!("malformed SYLT frame: {e}");
+                        continue;
+                    }
+                };
+                if !matches!(
+                    sylt.content_type,
+                    SyncTextContentType::Lyrics
+                        | SyncTextContentType::TextTranscription
+                        | SyncTextContentType::Other
+                ) {
+                    continue;
+                }
+
+                let timestamp_format = sylt.timestamp_format;
+                let entries = sylt.content.iter().filter_map(|(timestamp, text)| {
+                    let time = match timestamp_format {
+                        TimestampFormat::MS => Duration::from_millis((*timestamp).into()),
+                        TimestampFormat::MPEG => mpeg_frames_to_duration(*timestamp, sample_rate)?,
+                    };
+                    Some((time, text.to_string()))
+                });
+                let lyric = sylt_lyric(entries);
+                if !lyric.is_none() {
+                    synced.push((language(&sylt.language), lyric));
+                }
+            }
+            Frame::UnsynchronizedText(uslt) => {
+                if let Some(lyric) = parse_local_lyric(&uslt.content) {
+                    unsynced.push((language(&uslt.language), lyric));
+                }
+            }
+            _ => (),
+        }
+    }
+
+    #[cfg(feature = "i18n-local-lyric")]
+    let locale = sys_locale::get_locale();
+    #[cfg(not(feature = "i18n-local-lyric"))]
+    let locale: Option<String> = None;
+
+    if !synced.is_empty() {
+        debug!("found {} SYLT frames", synced.len());
+        let (olyric, tlyric) = select_by_language(synced, locale.as_deref())?;
+        // translation may be in USLT only, like what we embed
+        let tlyric = tlyric
+            .or_else(|| {
+                select_by_language(unsynced, locale.as_deref())
+                    .and_then(|(_, tlyric)| tlyric)
+                    .map(|(tlyric, _, _)| tlyric)
+            })
+            .unwrap_or_default();
+        return Some((olyric, tlyric, LrcMetadata::default()));
+    }
+    if unsynced.len() > 1 {
+        debug!("found {} USLT frames", unsynced.len());
+        let ((olyric, _, metadata), tlyric) = select_by_language(unsynced, locale.as_deref())?;
+        let tlyric = tlyric.map(|(tlyric, _, _)| tlyric).unwrap_or_default();
+        return Some((olyric, tlyric, metadata));
+    }
+    None
+}
+
+fn is_mp3(music_path: &Path) -> bool {
+    music_path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("mp3"))
+}
+
+fn read_mpeg(music_path: &Path) -> Option<MpegFile> {
+    let mut file = fs::File::open(music_path)
+        .map_err(|e| warn!("cannot read music file: {e}"))
+        .ok()?;
+    MpegFile::read_from(&mut file, ParseOptions::new())
+        .map_err(|e| warn!("cannot read music file: {e}"))
+        .ok()
+}
+
 pub static EXTRACT_TRANSLATED_LYRIC: OnceLock<bool> = OnceLock::new();
 
+fn read_local_lyric(path: &Path) -> Option<String> {
+    let bytes = fs::read(path)
+        .map_err(|e| error!("cannot read lyric from {path:?}: {e}"))
+        .ok()?;
+    decode_local_lyric(&bytes)
+        .map(Cow::into_owned)
+        .map_err(|e| error!("cannot decode lyric from {path:?}: {e}"))
+        .ok()
+}
+
 #[allow(unused_mut)]
-fn parse_local_lyric(lyric: &str) -> Option<(LyricOwned, LyricOwned)> {
-    let mut olyric =
-        crate::lyric_providers::utils::lrc_iter(lyric.trim_start_matches('\u{feff}').lines())
-            .map(|lyrics| Lyric::LineTimestamp(lyrics).into_owned())
-            .map_err(|e| error!("cannot parse lyric from hint: {e}"))
-            .ok()
-            .unwrap_or_default();
+fn parse_local_lyric(lyric: &str) -> Option<(LyricOwned, LyricOwned, LrcMetadata)> {
+    let lyric = lyric.trim_start_matches('\u{feff}');
+    let metadata = lrc_metadata(lyric.lines());
+    let mut olyric = crate::lyric_providers::utils::lrc_or_plain(lyric).into_owned();
     let mut tlyric = LyricOwned::None;
     #[cfg(feature = "i18n-local-lyric")]
     if EXTRACT_TRANSLATED_LYRIC.get().cloned().unwrap_or_default() {
@@ -145,17 +303,37 @@ fn parse_local_lyric(lyric: &str) -> Option<(LyricOwned, LyricOwned)> {
         return None;
     }
 
-    Some((olyric, tlyric))
+    Some((olyric, tlyric, metadata))
+}
+
+fn load_local_ttml(path: &Path) -> Option<(LyricOwned, LyricOwned, LrcMetadata)> {
+    let ttml = read_local_lyric(path)?;
+    let (olyric, tlyric) = parse_ttml(&ttml)
+        .map_err(|e| error!("cannot parse TTML lyric: {e}"))
+        .ok()?;
+
+    if olyric.is_none() && tlyric.is_none() {
+        return None;
+    }
+
+    Some((olyric, tlyric, LrcMetadata::default()))
 }
 
 #[allow(unused_mut)]
-fn load_local_lyric<P: AsRef<Path>>(path: P) -> Option<(LyricOwned, LyricOwned)> {
-    let olyric = fs::read_to_string(&path)
-        .map_err(|e| error!("cannot read lyric from hint: {e}"))
-        .ok()
+fn load_local_lyric<P: AsRef<Path>>(path: P) -> Option<(LyricOwned, LyricOwned, LrcMetadata)> {
+    // TTML carries its own translation
+    if path
+        .as_ref()
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("ttml"))
+    {
+        return load_local_ttml(path.as_ref());
+    }
+
+    let (olyric, metadata) = read_local_lyric(path.as_ref())
         .as_ref()
         .and_then(|lyric| parse_local_lyric(lyric))
-        .map(|(olyric, _)| olyric)
+        .map(|(olyric, _, metadata)| (olyric, metadata))
         .unwrap_or_default();
 
     #[cfg(feature = "i18n-local-lyric")]
@@ -163,12 +341,10 @@ fn load_local_lyric<P: AsRef<Path>>(path: P) -> Option<(LyricOwned, LyricOwned)>
         let mut translation_path = path.as_ref().to_owned();
         let lang = sys_locale::get_locale();
         translation_path.set_extension(format!("{}.lrc", lang.as_deref().unwrap_or("zh")));
-        fs::read_to_string(&translation_path)
-            .map_err(|e| error!("cannot read translated lyric from hint: {e}"))
-            .ok()
+        read_local_lyric(&translation_path)
             .as_ref()
             .and_then(|lyric| parse_local_lyric(lyric))
-            .map(|(tlyric, _)| tlyric)
+            .map(|(tlyric, _, _)| tlyric)
             .unwrap_or_default()
     };
     #[cfg(not(feature = "i18n-local-lyric"))]
@@ -178,5 +354,5 @@ fn load_local_lyric<P: AsRef<Path>>(path: P) -> Option<(LyricOwned, LyricOwned)>
         return None;
     }
 
-    Some((olyric, tlyric))
+    Some((olyric, tlyric, metadata))
 }
@@ -0,0 +1,84 @@
+use std::time::Duration;
+
+use gtk::glib::markup_escape_text;
+
+use crate::lyric_providers::{LyricLineOwned, LyricWordOwned};
+
+/// Returns byte length of the sung part of `line.text`.
+///
+/// Uses word timings if the line has them, otherwise interpolates
+/// linearly between `line.start_time` and its end,
+/// which is `line.end_time` if known, or `next_start`.
+///
+/// Without both, the whole line is considered sung.
+pub fn sung_len(line: &LyricLineOwned, next_start: Option<Duration>, elapsed: Duration) -> usize {
+    let total_chars = line.text.chars().count();
+    let next_start = line.end_time.or(next_start);
+
+    let sung_chars = if line.is_word_timed() {
+        sung_chars_by_words(&line.words, next_start, elapsed)
+    } else {
+        match next_start {
+            Some(end) => progress(line.start_time, end, elapsed) * total_chars as f64,
+            None => total_chars as f64,
+        }
+    };
+
+    let sung_chars = (sung_chars.floor() as usize).min(total_chars);
+    line.text
+        .char_indices()
+        .nth(sung_chars)
+        .map(|(index, _)| index)
+        .unwrap_or(line.text.len())
+}
+
+fn sung_chars_by_words(
+    words: &[LyricWordOwned],
+    next_start: Option<Duration>,
+    elapsed: Duration,
+) -> f64 {
+    let mut sung = 0.;
+    for (index, word) in words.iter().enumerate() {
+        let word_chars = word.text.chars().count() as f64;
+        if word.start_time > elapsed {
+            break;
+        }
+
+        let end = word
+            .end_time
+            .or_else(|| words.get(index + 1).map(|w| w.start_time))
+            .or(next_start);
+        sung += match end {
+            Some(end) => progress(word.start_time, end, elapsed) * word_chars,
+            None => word_chars,
+        };
+    }
+    sung
+}
+
+/// progress of `elapsed` inside `[start, end)`, in `0.0..=1.0`
+fn progress(start: Duration, end: Duration, elapsed: Duration) -> f64 {
+    if end <= start {
+        return 1.;
+    }
+    let passed = elapsed.saturating_sub(start).as_secs_f64();
+    (passed / (end - start).as_secs_f64()).clamp(0., 1.)
+}
+
+/// Pango markup which paints the first `sung_len` bytes of `text` with `color`,
+/// the remaining part keeps the label's own color.
+///
+/// `color` should be in `#rrggbb` format.
+pub fn karaoke_markup(text: &str, sung_len: usize, color: &str, alpha_percent: u8) -> String {
+    let (sung, rest) = text.split_at(sung_len);
+    if sung.is_empty() {
+        return markup_escape_text(rest).into();
+    }
+    // pango does not accept zero alpha
+    let alpha_percent = alpha_percent.max(1);
+    format!(
+        r#"<span foreground="{color}" fgalpha="{alpha_percent}%">{}</span>{}"#,
+        markup_escape_text(sung),
+        markup_escape_text(rest)
+    )
+}
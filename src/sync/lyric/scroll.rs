@@ -1,15 +1,22 @@
 use std::time::Duration;
 
 use gtk::glib::{self, ControlFlow, Priority, WeakRef};
+use gtk::prelude::*;
 use gtk::subclass::prelude::ObjectSubclassIsExt;
 
-use crate::app::{self, get_label};
-use crate::config::LyricDisplayMode;
+use crate::app::{self, get_label, get_lyric_labels, lyric_labels};
+use crate::config::{LyricDisplayMode, UnsyncedLyricMode};
 use crate::log::*;
 use crate::lyric_providers::{LyricLineOwned, LyricOwned};
+use crate::sync::lyric::context::{context_lines, current_line_index};
+use crate::sync::lyric::karaoke::{karaoke_markup, sung_len};
+use crate::sync::lyric::unsynced::{scroll_line_index, unsynced_lines};
 
 use crate::sync::{LyricState, TrackState, LYRIC, TRACK_PLAYING_STATE};
-use crate::utils::reset_lyric_labels;
+use crate::utils::{clear_context_labels, reset_lyric_labels};
+
+/// long enough for GTK to render one frame with `line-changed`
+const LINE_CHANGE_CLASS_DURATION: Duration = Duration::from_millis(50);
 
 pub fn register_lyric_display(app: WeakRef<app::Window>, interval: Duration) {
     glib::timeout_add_local_full(interval, Priority::HIGH, move || {
@@ -34,37 +41,139 @@ pub fn register_lyric_display(app: WeakRef<app::Window>, interval: Duration) {
     });
 }
 
+/// current lines of each lyric track
+struct Lines<'a> {
+    translation: Option<&'a LyricLineOwned>,
+    romanization: Option<&'a LyricLineOwned>,
+    origin: Option<&'a LyricLineOwned>,
+}
+
 fn set_lyric_with_mode(
     window: &app::Window,
-    translation: Option<&LyricLineOwned>,
-    origin: Option<&LyricLineOwned>,
+    lines: Lines<'_>,
+    origin_next_start: Option<Duration>,
+    elapsed: Duration,
 ) {
-    match window.imp().lyric_display_mode.get() {
+    let translation = hide_ended(window, lines.translation, elapsed);
+    let romanization = hide_ended(window, lines.romanization, elapsed);
+    let origin = hide_ended(window, lines.origin, elapsed);
+
+    let display_mode = window.imp().lyric_display_mode.get();
+    let romanization = match display_mode {
+        LyricDisplayMode::OriginRomanization | LyricDisplayMode::ShowAll => romanization,
+        _ => None,
+    };
+    set_lyric(window, romanization, "romanization", elapsed);
+
+    match display_mode {
         LyricDisplayMode::ShowBoth => {
-            set_lyric(window, translation.or(origin), "above");
-            set_lyric(window, translation.and(origin), "below");
+            set_lyric(window, translation.or(origin), "above", elapsed);
+            set_lyric(window, translation.and(origin), "below", elapsed);
         }
         LyricDisplayMode::ShowBothRev => {
-            set_lyric(window, origin, "above");
-            set_lyric(window, translation, "below");
+            set_lyric(window, origin, "above", elapsed);
+            set_lyric(window, translation, "below", elapsed);
         }
         LyricDisplayMode::Origin => {
-            set_lyric(window, origin, "above");
-            set_lyric(window, None, "below");
+            set_lyric(window, origin, "above", elapsed);
+            set_lyric(window, None, "below", elapsed);
         }
         LyricDisplayMode::PreferTranslation => {
-            set_lyric(window, translation.or(origin), "above");
-            set_lyric(window, None, "below");
+            set_lyric(window, translation.or(origin), "above", elapsed);
+            set_lyric(window, None, "below", elapsed);
+        }
+        LyricDisplayMode::Karaoke => {
+            set_karaoke_lyric(window, origin, origin_next_start, elapsed, "above");
+            set_lyric(window, translation, "below", elapsed);
+        }
+        LyricDisplayMode::OriginRomanization => {
+            set_lyric(window, origin, "above", elapsed);
+            set_lyric(window, None, "below", elapsed);
         }
+        LyricDisplayMode::ShowAll => {
+            set_lyric(window, origin, "above", elapsed);
+            set_lyric(window, translation, "below", elapsed);
+        }
+    }
+}
+
+/// ended lines are kept only if they should be dimmed
+fn hide_ended<'a>(
+    window: &app::Window,
+    line: Option<&'a LyricLineOwned>,
+    elapsed: Duration,
+) -> Option<&'a LyricLineOwned> {
+    if window.imp().dim_ended_line.get() {
+        line
+    } else {
+        line.filter(|line| !line_ended(window, line, elapsed))
+    }
+}
+
+fn line_ended(window: &app::Window, line: &LyricLineOwned, elapsed: Duration) -> bool {
+    line.is_over(elapsed, window.imp().line_max_hold.get())
+}
+
+fn set_ended_class(label: &gtk::Label, ended: bool) {
+    if ended {
+        label.add_css_class("ended");
+    } else {
+        label.remove_css_class("ended");
     }
 }
 
-fn set_lyric(window: &app::Window, text: Option<&LyricLineOwned>, position: &str) {
-    let text = text
+fn set_lyric(
+    window: &app::Window,
+    line: Option<&LyricLineOwned>,
+    position: &str,
+    elapsed: Duration,
+) {
+    let ended = line.is_some_and(|line| line_ended(window, line, elapsed));
+    let text = line
         .map(|LyricLineOwned { text, .. }| text.as_str().trim())
         .unwrap_or_default();
 
-    get_label(window, position).set_label(text);
+    let label = get_label(window, position);
+    label.remove_css_class("karaoke");
+    set_ended_class(&label, ended);
+    label.set_use_markup(false);
+    label.set_label(text);
+}
+
+fn set_karaoke_lyric(
+    window: &app::Window,
+    line: Option<&LyricLineOwned>,
+    next_start: Option<Duration>,
+    elapsed: Duration,
+    position: &str,
+) {
+    let Some(line) = line else {
+        set_lyric(window, None, position, elapsed);
+        return;
+    };
+
+    let sung_len = sung_len(line, next_start, elapsed);
+    let (color, alpha_percent) = karaoke_color(window);
+
+    let label = get_label(window, position);
+    label.add_css_class("karaoke");
+    set_ended_class(&label, line_ended(window, line, elapsed));
+    label.set_markup(&karaoke_markup(&line.text, sung_len, &color, alpha_percent));
+}
+
+/// reads `color` of `label.karaoke-sung` from current theme
+fn karaoke_color(window: &app::Window) -> (String, u8) {
+    let color = window.imp().karaoke_probe.color();
+    let to_u8 = |c: f32| (c.clamp(0., 1.) * 255.).round() as u8;
+    (
+        format!(
+            "#{:02x}{:02x}{:02x}",
+            to_u8(color.red()),
+            to_u8(color.green()),
+            to_u8(color.blue())
+        ),
+        (color.alpha().clamp(0., 1.) * 100.).round() as u8,
+    )
 }
 
 pub fn refresh_lyric(window: &app::Window, paused: bool) {
@@ -79,30 +188,122 @@ pub fn refresh_lyric(window: &app::Window, paused: bool) {
         |LyricState {
              origin,
              translation,
+             romanization,
          }| {
-            let system_time = window.imp().lyric_start.get().unwrap();
-            let elapsed = system_time.elapsed().ok();
-            let Some(elapsed) = elapsed else {
+            let Some(elapsed) = window.lyric_time() else {
                 return;
             };
-            match (origin, translation) {
-                (
-                    LyricOwned::LineTimestamp(origin_lyric),
-                    LyricOwned::LineTimestamp(translation_lyric),
-                ) => {
-                    let translation =
-                        crate::lyric_providers::utils::find_next_lyric(&elapsed, translation_lyric);
+            match origin {
+                LyricOwned::LineTimestamp(origin_lyric) => {
+                    let translation = current_line(&elapsed, translation);
+                    let romanization = current_line(&elapsed, romanization);
                     let origin =
                         crate::lyric_providers::utils::find_next_lyric(&elapsed, origin_lyric);
-                    set_lyric_with_mode(window, translation, origin);
+                    let origin_next_start = next_start_time(&elapsed, origin_lyric);
+                    set_lyric_with_mode(
+                        window,
+                        Lines {
+                            translation,
+                            romanization,
+                            origin,
+                        },
+                        origin_next_start,
+                        elapsed,
+                    );
+                    let current = current_line_index(&elapsed, origin_lyric);
+                    set_context_lyric(window, origin_lyric, current);
                 }
-                (LyricOwned::LineTimestamp(origin_lyric), _) => {
-                    let origin =
-                        crate::lyric_providers::utils::find_next_lyric(&elapsed, origin_lyric);
-                    set_lyric_with_mode(window, None, origin);
+                LyricOwned::NoTimestamp(text) => {
+                    set_unsynced_lyric(window, text, elapsed);
                 }
                 _ => (),
             }
         },
     );
 }
+
+fn set_context_lyric(window: &app::Window, lyric: &[LyricLineOwned], current: Option<usize>) {
+    let imp = window.imp();
+    let (previous, next) = context_lines(
+        lyric,
+        current,
+        imp.context_lines_before.get(),
+        imp.context_lines_after.get(),
+    );
+    let labels = lyric_labels(window);
+    for (label, text) in labels.previous.iter().zip(previous) {
+        label.set_label(text);
+    }
+    for (label, text) in labels.next.iter().zip(next) {
+        label.set_label(text);
+    }
+
+    if imp.current_line.replace(current) != current {
+        play_line_change(window);
+    }
+}
+
+/// Adds css class `line-changed` to all lyric labels for a moment,
+/// so that themes can animate line changes with `transition`.
+fn play_line_change(window: &app::Window) {
+    let labels = get_lyric_labels(window);
+    for label in &labels {
+        label.add_css_class("line-changed");
+    }
+    glib::timeout_add_local_once(LINE_CHANGE_CLASS_DURATION, move || {
+        for label in labels {
+            label.remove_css_class("line-changed");
+        }
+    });
+}
+
+/// only timestamped lyric has current line
+fn current_line<'a>(elapsed: &Duration, lyric: &'a LyricOwned) -> Option<&'a LyricLineOwned> {
+    match lyric {
+        LyricOwned::LineTimestamp(lyric) => {
+            crate::lyric_providers::utils::find_next_lyric(elapsed, lyric)
+        }
+        _ => None,
+    }
+}
+
+/// shows two lines of unsynced lyric, chosen by `unsynced_lyric_mode`
+fn set_unsynced_lyric(window: &app::Window, text: &str, elapsed: Duration) {
+    let lines = unsynced_lines(text);
+    let length = TRACK_PLAYING_STATE
+        .with_borrow(|TrackState { metainfo, .. }| metainfo.as_ref().and_then(|meta| meta.length));
+
+    let index = match (window.imp().unsynced_lyric_mode.get(), length) {
+        (UnsyncedLyricMode::Scroll, Some(length)) => {
+            scroll_line_index(lines.len(), elapsed, length)
+        }
+        // cannot estimate pace without track length
+        _ => {
+            let index = window
+                .imp()
+                .unsynced_line
+                .get()
+                .min(lines.len().saturating_sub(1));
+            window.imp().unsynced_line.set(index);
+            index
+        }
+    };
+
+    set_lyric(window, None, "romanization", elapsed);
+    clear_context_labels(window);
+    for (offset, position) in ["above", "below"].into_iter().enumerate() {
+        let label = get_label(window, position);
+        label.remove_css_class("karaoke");
+        label.remove_css_class("ended");
+        label.set_use_markup(false);
+        label.set_label(lines.get(index + offset).copied().unwrap_or_default());
+    }
+}
+
+/// start time of the first line not started yet
+fn next_start_time(elapsed: &Duration, lyric: &[LyricLineOwned]) -> Option<Duration> {
+    lyric
+        .iter()
+        .map(|LyricLineOwned { start_time, .. }| *start_time)
+        .find(|start_time| start_time > elapsed)
+}
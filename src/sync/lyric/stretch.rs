@@ -0,0 +1,115 @@
+//! linear time-stretch of lyric
+//!
+//! lyric of a different master or edit of the track drifts gradually:
+//! right at the start, seconds off by the end.
+//! a line at lyric time `t` is shown at `t * scale + offset_ms`,
+//! before lyric offset is applied.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::lyric_providers::LyricLineOwned;
+
+/// sync points closer than this give a poor estimation of the scale
+pub const MIN_SYNC_POINT_DISTANCE: Duration = Duration::from_secs(10);
+
+/// scales beyond this range are surely not the same song
+const SCALE_RANGE: std::ops::RangeInclusive<f64> = 0.5..=2.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub struct TimeStretch {
+    pub scale: f64,
+    pub offset_ms: i64,
+}
+
+impl Default for TimeStretch {
+    fn default() -> Self {
+        Self {
+            scale: 1.,
+            offset_ms: 0,
+        }
+    }
+}
+
+/// the start of a line, heard at `position` of the track
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SyncPoint {
+    pub lyric_time: Duration,
+    pub position: Duration,
+}
+
+impl TimeStretch {
+    /// stretches lyric of a song of `lyric_length` to `track_length`
+    ///
+    /// returns `None` if the lengths are the same, or differ more than `max_diff`
+    pub fn fit_length(
+        lyric_length: Duration,
+        track_length: Duration,
+        max_diff: Duration,
+    ) -> Option<Self> {
+        let diff = lyric_length.abs_diff(track_length);
+        if lyric_length.is_zero() || diff.is_zero() || diff > max_diff {
+            return None;
+        }
+        Some(Self {
+            scale: track_length.as_secs_f64() / lyric_length.as_secs_f64(),
+            offset_ms: 0,
+        })
+    }
+
+    /// solves the stretch which shows both lines when they are heard,
+    /// with `lyric_offset_ms` applied as well
+    ///
+    /// returns `None` if the points are too close, or the scale is unlikely
+    pub fn from_sync_points(
+        first: SyncPoint,
+        second: SyncPoint,
+        lyric_offset_ms: i64,
+    ) -> Option<Self> {
+        if first.lyric_time.abs_diff(second.lyric_time) < MIN_SYNC_POINT_DISTANCE {
+            return None;
+        }
+        let ms = |time: Duration| time.as_secs_f64() * 1000.;
+        let scale = (ms(second.position) - ms(first.position))
+            / (ms(second.lyric_time) - ms(first.lyric_time));
+        if !SCALE_RANGE.contains(&scale) {
+            return None;
+        }
+        let intercept = ms(first.position) - ms(first.lyric_time) * scale;
+        Some(Self {
+            scale,
+            offset_ms: intercept.round() as i64 - lyric_offset_ms,
+        })
+    }
+
+    pub fn is_identity(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// when a line at lyric `time` is shown
+    pub fn apply(&self, time: Duration) -> Duration {
+        let ms = time.as_secs_f64() * 1000. * self.scale + self.offset_ms as f64;
+        Duration::from_millis(ms.round().max(0.) as u64)
+    }
+
+    /// the lyric time shown at `time`, reverse of [`TimeStretch::apply`]
+    pub fn lyric_time(&self, time: Duration) -> Duration {
+        let ms = (time.as_secs_f64() * 1000. - self.offset_ms as f64) / self.scale;
+        Duration::from_millis(ms.round().max(0.) as u64)
+    }
+
+    /// bakes the stretch into `line` and its word timings
+    pub fn apply_to_line(&self, line: &mut LyricLineOwned) {
+        line.start_time = self.apply(line.start_time);
+        if let Some(end_time) = &mut line.end_time {
+            *end_time = self.apply(*end_time);
+        }
+        for word in &mut line.words {
+            word.start_time = self.apply(word.start_time);
+            if let Some(end_time) = &mut word.end_time {
+                *end_time = self.apply(*end_time);
+            }
+        }
+    }
+}
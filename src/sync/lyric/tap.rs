@@ -0,0 +1,123 @@
+//! tap-to-sync: plain text lines get timestamps one by one,
+//! as the user taps at the start of each line
+
+use std::time::Duration;
+
+use crate::lyric_providers::{LyricLineOwned, LyricOwned};
+use crate::sync::lyric::edit::pair_lines;
+
+#[derive(Clone, Debug, Default)]
+pub struct TapSync {
+    lines: Vec<String>,
+    start_times: Vec<Duration>,
+}
+
+impl TapSync {
+    /// blank lines are skipped, as there's nothing to tap on
+    pub fn new(text: &str) -> Self {
+        let lines = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_owned)
+            .collect();
+        Self {
+            lines,
+            start_times: vec![],
+        }
+    }
+
+    /// the line to tap next
+    pub fn current_line(&self) -> Option<&str> {
+        self.lines.get(self.tapped()).map(String::as_str)
+    }
+
+    pub fn next_line(&self) -> Option<&str> {
+        self.lines.get(self.tapped() + 1).map(String::as_str)
+    }
+
+    pub fn tapped(&self) -> usize {
+        self.start_times.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.tapped() == self.len()
+    }
+
+    /// starts current line at `position`, returns `false` if all lines are tapped,
+    /// or if `position` is before the last tap, as the player was seeked back
+    pub fn tap(&mut self, position: Duration) -> bool {
+        if self.is_finished() || self.start_times.last() > Some(&position) {
+            return false;
+        }
+        self.start_times.push(position);
+        true
+    }
+
+    /// forgets the timestamp of the last tapped line
+    pub fn undo(&mut self) {
+        self.start_times.pop();
+    }
+
+    /// tapped lines, in order of the text
+    pub fn into_lyric(self) -> LyricOwned {
+        if self.start_times.is_empty() {
+            return LyricOwned::None;
+        }
+        let lines = self
+            .lines
+            .into_iter()
+            .zip(self.start_times)
+            .map(|(text, start_time)| LyricLineOwned {
+                text,
+                start_time,
+                ..Default::default()
+            })
+            .collect();
+        LyricOwned::LineTimestamp(lines)
+    }
+}
+
+/// times translation or romanization of `old` lines as the tapped `new` ones
+///
+/// returns `None` if texts of tapped lines are not those of `old` ones
+pub fn retime_paired_lines(
+    old: &[LyricLineOwned],
+    paired: &[LyricLineOwned],
+    new: &[LyricLineOwned],
+) -> Option<Vec<LyricLineOwned>> {
+    // blank lines are not tapped
+    let old: Vec<_> = pair_lines(old, paired)
+        .into_iter()
+        .filter(|line| !line.line.text.trim().is_empty())
+        .collect();
+    if new.len() > old.len()
+        || new
+            .iter()
+            .zip(&old)
+            .any(|(new, old)| new.text != old.line.text.trim())
+    {
+        return None;
+    }
+
+    Some(
+        new.iter()
+            .zip(old)
+            .filter_map(|(new, old)| {
+                Some(LyricLineOwned {
+                    text: old.translation?.text,
+                    start_time: new.start_time,
+                    ..Default::default()
+                })
+            })
+            .collect(),
+    )
+}
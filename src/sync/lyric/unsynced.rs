@@ -0,0 +1,19 @@
+use std::time::Duration;
+
+/// non-empty lines of an unsynced lyric
+pub fn unsynced_lines(text: &str) -> Vec<&str> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+/// Returns index of the line to show, assuming lines are evenly
+/// spread over the whole track.
+pub fn scroll_line_index(line_count: usize, elapsed: Duration, length: Duration) -> usize {
+    if line_count == 0 || length.is_zero() {
+        return 0;
+    }
+    let progress = (elapsed.as_secs_f64() / length.as_secs_f64()).clamp(0., 1.);
+    ((progress * line_count as f64) as usize).min(line_count - 1)
+}
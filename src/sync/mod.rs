@@ -33,10 +33,11 @@ pub struct TrackState {
 pub struct LyricState {
     pub origin: LyricOwned,
     pub translation: LyricOwned,
+    pub romanization: LyricOwned,
 }
 
 thread_local! {
-    static LYRIC: RefCell<LyricState> = const { RefCell::new(LyricState { origin: LyricOwned::None, translation: LyricOwned::None }) };
+    static LYRIC: RefCell<LyricState> = const { RefCell::new(LyricState { origin: LyricOwned::None, translation: LyricOwned::None, romanization: LyricOwned::None }) };
     /// A global variable that contains current playing state (excluding lyrics)
     /// including: track_id, paused, cache_path
     pub static TRACK_PLAYING_STATE: RefCell<TrackState> = RefCell::new(TrackState::default());
@@ -45,20 +46,22 @@ thread_local! {
 pub static ENABLE_LOCAL_LYRIC: OnceLock<bool> = OnceLock::new();
 
 pub(crate) mod actions;
-#[cfg(feature = "export-lyric")]
-pub use actions::register_export_lyric;
 #[cfg(feature = "import-lyric")]
 pub use actions::register_import_lyric;
 pub use actions::{
-    register_connect, register_disconnect, register_refetch_lyric, register_reload_lyric,
-    register_remove_lyric, register_search_lyric,
+    register_connect, register_disconnect, register_edit_lyric, register_lyric_offset,
+    register_lyric_stretch, register_refetch_lyric, register_reload_lyric, register_remove_lyric,
+    register_search_lyric, register_sync_lyric_now, register_tap_sync_lyric,
 };
+#[cfg(feature = "export-lyric")]
+pub use actions::{register_embed_lyric, register_export_lyric};
 
 #[cfg(feature = "action-event")]
 pub use actions::{init_play_action_channel, PlayAction, PLAY_ACTION};
 
 pub use interop::{register_sync_task, PlayerId};
 pub use utils::{
-    extract_translated_lyric, filter_original_lyric, fuzzy_match_song, get_lyric_cache_path,
-    set_current_lyric,
+    check_lrc_metadata, extract_translated_lyric, filter_original_lyric, fuzzy_match_song,
+    get_current_lyric, get_lyric_cache_path, get_playing_track, is_track_playing,
+    set_current_lyric, LrcMismatch,
 };
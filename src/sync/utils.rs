@@ -7,13 +7,17 @@ use sorensen::distance;
 
 use crate::app;
 use crate::log::*;
+use crate::lyric_providers::utils::LrcMetadata;
 use crate::lyric_providers::{LyricLineOwned, SongInfo};
 
-use super::{LyricState, TrackState, LYRIC, TRACK_PLAYING_STATE};
+use super::{LyricState, TrackMeta, TrackState, LYRIC, TRACK_PLAYING_STATE};
 
 pub fn clean_lyric(window: &app::Window) {
     LYRIC.set(LyricState::default());
     window.imp().lyric_offset_ms.set(0);
+    window.imp().lyric_stretch.set(None);
+    window.imp().sync_points.borrow_mut().clear();
+    window.imp().unsynced_line.set(0);
 }
 
 /// both singer and album are optional non-empty string
@@ -24,7 +28,7 @@ pub fn match_likely_lyric<'a>(
     length: Option<Duration>,
     search_result: &'a [SongInfo],
     length_toleration_ms: u128,
-) -> Option<(&'a str, u8)> {
+) -> Option<(&'a SongInfo, u8)> {
     length
         .and_then(|leng| {
             search_result
@@ -110,7 +114,51 @@ pub fn match_likely_lyric<'a>(
                 .map(|(s, _)| (s, 0))
         })
         .or(search_result.first().map(|song| (song, 2)))
-        .map(|(song, weight)| (song.id.as_str(), weight))
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum LrcMismatch {
+    #[error("title in lrc is {0:?}")]
+    Title(String),
+    #[error("artist in lrc is {0:?}")]
+    Artist(String),
+    #[error("length in lrc is {0:?}")]
+    Length(Duration),
+}
+
+/// checks `[ti:]`, `[ar:]` and `[length:]` against the playing track
+///
+/// tags missing from either side are not checked
+pub fn check_lrc_metadata(
+    lrc: &LrcMetadata,
+    track: &TrackMeta,
+    length_toleration_ms: u128,
+) -> Result<(), LrcMismatch> {
+    let likely_same = |a: &str, b: &str| {
+        let (a, b) = (a.trim().to_lowercase(), b.trim().to_lowercase());
+        a.contains(&b) || b.contains(&a)
+    };
+
+    if let (Some(lrc_title), Some(title)) = (&lrc.title, &track.title) {
+        if !likely_same(lrc_title, title) {
+            return Err(LrcMismatch::Title(lrc_title.clone()));
+        }
+    }
+    if let (Some(lrc_artist), Some(artists)) = (&lrc.artist, &track.artists) {
+        if !artists.is_empty() && !artists.iter().any(|a| likely_same(lrc_artist, a)) {
+            return Err(LrcMismatch::Artist(lrc_artist.clone()));
+        }
+    }
+    if let (Some(lrc_length), Some(length)) = (lrc.length, track.length) {
+        if lrc_length.as_millis().abs_diff(length.as_millis()) > length_toleration_ms {
+            return Err(LrcMismatch::Length(lrc_length));
+        }
+    }
+    Ok(())
+}
+
+pub fn get_current_lyric() -> LyricState {
+    LYRIC.with_borrow(LyricState::clone)
 }
 
 pub fn set_current_lyric(lyric: LyricState) {
@@ -121,6 +169,15 @@ pub fn get_lyric_cache_path() -> Option<PathBuf> {
     TRACK_PLAYING_STATE.with_borrow(|TrackState { cache_path, .. }| cache_path.as_ref().cloned())
 }
 
+pub fn get_playing_track() -> TrackState {
+    TRACK_PLAYING_STATE.with_borrow(TrackState::clone)
+}
+
+/// whether `track` from [`get_playing_track`] is still playing
+pub fn is_track_playing(track: &TrackState) -> bool {
+    TRACK_PLAYING_STATE.with_borrow(|TrackState { metainfo, .. }| *metainfo == track.metainfo)
+}
+
 pub fn fuzzy_match_song(
     title: &[char],
     album: Option<&[char]>,
@@ -161,14 +218,20 @@ pub fn filter_original_lyric(
     let tlyric = tlyric
         .as_ref()
         .iter()
-        .map(|LyricLineOwned { text, start_time }| (start_time, text))
+        .map(
+            |LyricLineOwned {
+                 text, start_time, ..
+             }| (start_time, text),
+        )
         .collect::<HashMap<_, _>>();
     let tlyric_lines = lyric
         .as_ref()
         .iter()
-        .filter(|LyricLineOwned { text, start_time }| {
-            !tlyric.contains_key(start_time) || tlyric[start_time] != text
-        })
+        .filter(
+            |LyricLineOwned {
+                 text, start_time, ..
+             }| { !tlyric.contains_key(start_time) || tlyric[start_time] != text },
+        )
         .cloned()
         .collect::<Vec<_>>();
     tlyric_lines
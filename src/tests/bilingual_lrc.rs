@@ -0,0 +1,62 @@
+use std::time::Duration;
+
+use anyhow::Result;
+
+use crate::lyric_providers::utils::{lrc_iter, make_lrc_body};
+use crate::lyric_providers::LyricLine;
+use crate::sync::actions::utils::merge_bilingual_lines;
+use crate::sync::{extract_translated_lyric, filter_original_lyric};
+use crate::tests::line;
+
+#[test]
+fn bilingual_lrc_round_trip() -> Result<()> {
+    let origin = [line("Hello", 1000), line("", 2000), line("world", 3000)];
+    let translation = [line("你好", 1000), line("", 2000), line("世界", 3000)];
+
+    let merged = merge_bilingual_lines(&origin, &translation, None);
+    let lrc = make_lrc_body(&merged);
+    assert_eq!(
+        lrc,
+        "[00:01.000]Hello\n[00:01.000]你好\n[00:02.000]\n[00:03.000]world\n[00:03.000]世界\n"
+    );
+
+    let lyrics = lrc_iter(lrc.lines())?
+        .into_iter()
+        .map(LyricLine::into_owned)
+        .collect::<Vec<_>>();
+    let tlyric = extract_translated_lyric(&lyrics);
+    let olyric = filter_original_lyric(lyrics, &tlyric);
+    assert_eq!(tlyric, [line("你好", 1000), line("世界", 3000)]);
+    assert_eq!(olyric, origin);
+    Ok(())
+}
+
+#[test]
+fn bilingual_lrc_skips_untranslated_lines() {
+    let origin = [line("Hello", 1000), line("Oh", 2000), line("world", 3000)];
+    let translation = [line("你好", 1000), line("Oh", 2000), line("世界", 3100)];
+
+    let merged = merge_bilingual_lines(&origin, &translation, None);
+    assert_eq!(
+        merged,
+        [
+            line("Hello", 1000),
+            line("你好", 1000),
+            line("Oh", 2000),
+            line("world", 3000)
+        ]
+    );
+}
+
+#[test]
+fn bilingual_lrc_same_line() {
+    let mut origin = line("Hello", 1000);
+    origin.end_time = Some(Duration::from_millis(2000));
+    origin.words = vec![Default::default(); 2];
+    let translation = [line("你好", 1000)];
+
+    let merged = merge_bilingual_lines(&[origin, line("world", 3000)], &translation, Some(" / "));
+    let mut expected = line("Hello / 你好", 1000);
+    expected.end_time = Some(Duration::from_millis(2000));
+    assert_eq!(merged, [expected, line("world", 3000)]);
+}
@@ -0,0 +1,129 @@
+use std::fs;
+use std::time::Duration;
+
+use anyhow::Result;
+
+use crate::lyric_providers::command::{
+    songs_from_output, CommandProvider, CommandProviderConfig, Error,
+};
+use crate::lyric_providers::{LyricOwned, LyricParse, LyricProvider};
+
+/// runs `script` by `sh`, with `args` as `$0`, `$1`...
+fn provider(script: &str, args: &[&str], timeout: &str) -> CommandProvider {
+    CommandProvider::new(CommandProviderConfig {
+        id: "script".into(),
+        command: "sh".into(),
+        args: ["-c", script]
+            .iter()
+            .chain(args)
+            .map(|arg| arg.to_string())
+            .collect(),
+        timeout: timeout.into(),
+        ..Default::default()
+    })
+    .unwrap()
+}
+
+const LYRIC_SCRIPT: &str = r#"
+read -r request
+case "$request" in
+    *'"action":"search"'*)
+        printf '%s\n' '[{"id": "42", "title": "Hello", "singer": "A, B", "length": 200.5},'
+        printf '%s\n' ' {"id": "43", "title": "Hello (Live)", "album": ""}]'
+        ;;
+    *'"id":"42"'*)
+        printf '%s\n' '{"lyric": "[00:01.00]Hello\n[00:02.00]world", "translation": "[00:01.00]你好"}'
+        ;;
+    *'"id":"43"'*)
+        printf '[00:01.00]Hello\n[00:02.00]world\n'
+        ;;
+    *)
+        echo "unknown request: $request" >&2
+        exit 1
+        ;;
+esac
+"#;
+
+#[tokio::test]
+async fn search_and_query_by_script() -> Result<()> {
+    let provider = provider(LYRIC_SCRIPT, &[], "5s");
+
+    let songs = provider
+        .search_song_detailed("", &["A", "B"], "Hello", Some(Duration::from_secs(200)))
+        .await?;
+    assert_eq!(songs.len(), 2);
+    assert_eq!(songs[0].singer, "A, B");
+    assert_eq!(songs[0].length, Duration::from_millis(200_500));
+    assert_eq!(songs[1].album, None);
+    assert_eq!(songs[1].length, Duration::ZERO);
+
+    let store = provider.query_lyric("42").await?;
+    let LyricOwned::LineTimestamp(lines) = provider.parse_translated_lyric(&store) else {
+        anyhow::bail!("no translation from script");
+    };
+    assert_eq!(lines[0].text, "你好");
+
+    let store = provider.query_lyric("43").await?;
+    let LyricOwned::LineTimestamp(lines) = provider.parse_lyric(&store) else {
+        anyhow::bail!("no lyric from script");
+    };
+    assert_eq!(lines[1].text, "world");
+    assert!(provider.parse_translated_lyric(&store).is_none());
+
+    let error = provider.search_song("Hello").await.unwrap_err();
+    assert!(error.to_string().contains("unknown request"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn send_request_in_stdin() -> Result<()> {
+    let path = std::env::temp_dir().join(format!("waylyrics-command-{}", std::process::id()));
+    let path_arg = path.to_string_lossy();
+    let provider = provider(r#"cat > "$0""#, &[&path_arg], "5s");
+
+    let songs = provider
+        .search_song_detailed("Album", &["A"], "Hello", None)
+        .await?;
+    assert!(songs.is_empty());
+
+    let request = fs::read_to_string(&path)?;
+    fs::remove_file(&path)?;
+    assert_eq!(
+        request,
+        "{\"action\":\"search\",\"title\":\"Hello\",\"artists\":[\"A\"],\"album\":\"Album\",\"length\":null}\n"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn kill_slow_command() {
+    let provider = provider("sleep 5", &[], "200ms");
+    let error = provider.search_song("Hello").await.unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<Error>(),
+        Some(Error::Timeout(_))
+    ));
+}
+
+#[test]
+fn reject_malformed_timeout() {
+    let config = CommandProviderConfig {
+        id: "script".into(),
+        timeout: "10".into(),
+        ..Default::default()
+    };
+    assert!(matches!(
+        CommandProvider::new(config),
+        Err(Error::InvalidTimeout { .. })
+    ));
+}
+
+#[test]
+fn skip_length_out_of_range() {
+    let songs = songs_from_output(
+        r#"[{"id": "1", "title": "A", "length": 1e20}, {"id": "2", "title": "B", "length": -1}]"#,
+    )
+    .unwrap();
+    assert_eq!(songs[0].length, Duration::ZERO);
+    assert_eq!(songs[1].length, Duration::ZERO);
+}
@@ -0,0 +1,50 @@
+use std::time::Duration;
+
+use crate::lyric_providers::LyricLineOwned;
+use crate::sync::lyric::context::{context_lines, current_line_index};
+
+fn lyric(texts: &[&str]) -> Vec<LyricLineOwned> {
+    texts
+        .iter()
+        .enumerate()
+        .map(|(index, text)| LyricLineOwned {
+            text: text.to_string(),
+            start_time: Duration::from_secs(index as u64 * 10 + 10),
+            ..Default::default()
+        })
+        .collect()
+}
+
+#[test]
+fn current_index() {
+    let lyric = lyric(&["a", "b", "c"]);
+    assert_eq!(current_line_index(&Duration::from_secs(5), &lyric), None);
+    assert_eq!(
+        current_line_index(&Duration::from_secs(10), &lyric),
+        Some(0)
+    );
+    assert_eq!(
+        current_line_index(&Duration::from_secs(25), &lyric),
+        Some(1)
+    );
+    assert_eq!(
+        current_line_index(&Duration::from_secs(99), &lyric),
+        Some(2)
+    );
+}
+
+#[test]
+fn lines_around_current() {
+    let lyric = lyric(&["a", "b", "c", "d", "e"]);
+    let (previous, next) = context_lines(&lyric, Some(1), 2, 2);
+    assert_eq!(previous, vec!["a", ""]);
+    assert_eq!(next, vec!["c", "d"]);
+}
+
+#[test]
+fn nothing_started() {
+    let lyric = lyric(&["a", "b"]);
+    let (previous, next) = context_lines(&lyric, None, 1, 3);
+    assert_eq!(previous, vec![""]);
+    assert_eq!(next, vec!["a", "b", ""]);
+}
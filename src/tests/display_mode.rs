@@ -0,0 +1,18 @@
+use strum::IntoEnumIterator;
+
+use crate::config::LyricDisplayMode;
+
+/// display mode is saved to gsettings as string
+#[test]
+fn display_mode_round_trip() {
+    for mode in LyricDisplayMode::iter() {
+        let saved = mode.to_string();
+        let loaded: LyricDisplayMode = saved.parse().unwrap();
+        assert_eq!(loaded.to_string(), saved);
+    }
+    assert_eq!(LyricDisplayMode::ShowAll.to_string(), "show_all");
+    assert_eq!(
+        LyricDisplayMode::OriginRomanization.to_string(),
+        "origin_romanization"
+    );
+}
@@ -0,0 +1,188 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::Result;
+use serde_json::json;
+
+use crate::lyric_providers::http::{
+    fill_template, json_path, songs_from_response, HttpProvider, HttpProviderConfig, LengthUnit,
+};
+use crate::lyric_providers::registry::{register_custom_providers, RegistryError};
+use crate::lyric_providers::{LyricOwned, LyricParse, LyricProvider};
+
+fn config(base: &str) -> HttpProviderConfig {
+    HttpProviderConfig {
+        id: "stand-in".into(),
+        search_url: format!("{base}/search?q={{title}}&artist={{artist}}&d={{duration}}"),
+        results_path: "data.songs".into(),
+        id_path: "id".into(),
+        title_path: "name".into(),
+        artist_path: Some("artists".into()),
+        album_path: Some("album.name".into()),
+        length_path: Some("duration".into()),
+        length_unit: LengthUnit::Milliseconds,
+        lyric_url: format!("{base}/lyric/{{id}}"),
+        lrc_path: "lrc".into(),
+        translation_path: Some("trans".into()),
+        headers: [("X-Token".to_owned(), "secret".to_owned())].into(),
+        ..Default::default()
+    }
+}
+
+/// answers requests by path, and records their request line and headers
+fn serve_stand_in() -> (String, Arc<Mutex<Vec<String>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let requests = Arc::new(Mutex::new(vec![]));
+
+    let recorded = requests.clone();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            let mut request = String::new();
+            let mut reader = BufReader::new(&stream);
+            while let Ok(n) = reader.read_line(&mut request) {
+                if n == 0 || request.ends_with("\r\n\r\n") {
+                    break;
+                }
+            }
+
+            let body = if request.starts_with("GET /search") {
+                json!({"data": {"songs": [
+                    {"id": 42, "name": "Hello", "artists": ["A", "B"],
+                     "album": {"name": "Album"}, "duration": 200500},
+                    {"name": "no id"},
+                ]}})
+            } else if request.starts_with("GET /lyric/42 ") {
+                json!({
+                    "lrc": "[00:01.00]Hello\n[00:02.50]world",
+                    "trans": "[00:01.00]你好\n[00:02.50]世界",
+                })
+            } else {
+                json!({})
+            };
+            recorded.lock().unwrap().push(request);
+
+            let body = body.to_string();
+            let _ = write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+        }
+    });
+    (base, requests)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn search_and_query_stand_in() -> Result<()> {
+    let (base, requests) = serve_stand_in();
+    let provider = HttpProvider::new(config(&base));
+
+    let songs = provider
+        .search_song_detailed(
+            "",
+            &["A", "B"],
+            "Hello World",
+            Some(Duration::from_secs(200)),
+        )
+        .await?;
+    assert_eq!(songs.len(), 1);
+    let song = &songs[0];
+    assert_eq!(song.id, "42");
+    assert_eq!(song.title, "Hello");
+    assert_eq!(song.singer, "A, B");
+    assert_eq!(song.album.as_deref(), Some("Album"));
+    assert_eq!(song.length, Duration::from_millis(200_500));
+
+    let store = provider.query_lyric(&song.id).await?;
+    let LyricOwned::LineTimestamp(lines) = provider.parse_lyric(&store) else {
+        anyhow::bail!("no lyric from stand-in");
+    };
+    assert_eq!(lines[1].text, "world");
+    let LyricOwned::LineTimestamp(lines) = provider.parse_translated_lyric(&store) else {
+        anyhow::bail!("no translation from stand-in");
+    };
+    assert_eq!(lines[0].text, "你好");
+
+    let requests = requests.lock().unwrap();
+    assert!(requests[0].starts_with("GET /search?q=Hello%20World&artist=A%2C%20B&d=200 "));
+    assert!(requests
+        .iter()
+        .all(|request| request.to_lowercase().contains("x-token: secret")));
+    Ok(())
+}
+
+#[test]
+fn look_up_json_path() {
+    let value = json!({"data": {"songs": [{"id": 1}, {"id": 2}]}});
+    assert_eq!(json_path(&value, "data.songs.1.id"), Some(&json!(2)));
+    assert_eq!(json_path(&value, ""), Some(&value));
+    assert_eq!(json_path(&value, "data.songs.x"), None);
+    assert_eq!(json_path(&value, "data.albums"), None);
+}
+
+#[test]
+fn fill_url_template() {
+    let url = fill_template(
+        "http://localhost/s/{title}?a={album}&x={unknown}",
+        &[("title", "a/b c"), ("album", "")],
+    );
+    assert_eq!(url, "http://localhost/s/a%2Fb%20c?a=&x={unknown}");
+}
+
+#[test]
+fn parse_search_response() {
+    let config = HttpProviderConfig {
+        results_path: String::new(),
+        id_path: "id".into(),
+        title_path: "title".into(),
+        length_path: Some("length".into()),
+        ..Default::default()
+    };
+
+    let songs = songs_from_response(
+        &config,
+        &json!([
+            {"id": "a", "title": "A", "length": "185.5"},
+            {"id": "b", "title": "B"},
+            {"id": "c", "title": "C", "length": 1e20},
+            {"id": "d", "title": "D", "length": -1},
+        ]),
+    )
+    .unwrap();
+    assert_eq!(songs[0].length, Duration::from_millis(185_500));
+    assert_eq!(songs[0].singer, "");
+    assert_eq!(songs[1].length, Duration::ZERO);
+    // lengths out of range are skipped
+    assert_eq!(songs[2].length, Duration::ZERO);
+    assert_eq!(songs[3].length, Duration::ZERO);
+
+    assert!(songs_from_response(&config, &json!({"error": "oops"})).is_err());
+}
+
+#[test]
+fn reject_bad_custom_id() {
+    let provider = |id: &str| {
+        Box::new(HttpProvider::new(HttpProviderConfig {
+            id: id.into(),
+            ..Default::default()
+        })) as Box<dyn LyricProvider>
+    };
+
+    assert!(matches!(
+        register_custom_providers(vec![provider("my lyrics")]),
+        Err(RegistryError::InvalidId(_))
+    ));
+    assert!(matches!(
+        register_custom_providers(vec![provider("netease")]),
+        Err(RegistryError::DuplicatedId(_))
+    ));
+    assert!(matches!(
+        register_custom_providers(vec![provider("mine"), provider("mine")]),
+        Err(RegistryError::DuplicatedId(_))
+    ));
+}
@@ -0,0 +1,146 @@
+use std::time::Duration;
+
+use crate::lyric_providers::id3::{
+    make_sylt, mpeg_frames_to_duration, select_by_language, sylt_lyric,
+};
+use crate::lyric_providers::{LyricLineOwned, LyricOwned, LyricWordOwned};
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+#[test]
+fn sylt_lines() {
+    let lyric = sylt_lyric([
+        (ms(2000), " second ".to_owned()),
+        (ms(1000), "first".to_owned()),
+        (ms(3000), String::new()),
+    ]);
+
+    let line = |text: &str, start_ms| LyricLineOwned {
+        text: text.into(),
+        start_time: ms(start_ms),
+        ..Default::default()
+    };
+    assert_eq!(
+        lyric,
+        LyricOwned::LineTimestamp(vec![
+            line("first", 1000),
+            line("second", 2000),
+            line("", 3000),
+        ])
+    );
+}
+
+#[test]
+fn sylt_syllables() {
+    let lyric = sylt_lyric([
+        (ms(1000), "Hello ".to_owned()),
+        (ms(1500), "world".to_owned()),
+        (ms(3000), "\nnext".to_owned()),
+        (ms(3500), " line\n".to_owned()),
+        (ms(5000), "solo".to_owned()),
+    ]);
+
+    let word = |text: &str, start_ms| LyricWordOwned {
+        text: text.into(),
+        start_time: ms(start_ms),
+        end_time: None,
+    };
+    assert_eq!(
+        lyric,
+        LyricOwned::LineTimestamp(vec![
+            LyricLineOwned {
+                text: "Hello world".into(),
+                start_time: ms(1000),
+                end_time: None,
+                words: vec![word("Hello ", 1000), word("world", 1500)],
+            },
+            LyricLineOwned {
+                text: "next line".into(),
+                start_time: ms(3000),
+                end_time: None,
+                words: vec![word("next", 3000), word(" line", 3500)],
+            },
+            LyricLineOwned {
+                text: "solo".into(),
+                start_time: ms(5000),
+                end_time: None,
+                words: vec![],
+            },
+        ])
+    );
+}
+
+#[test]
+fn empty_sylt() {
+    assert_eq!(sylt_lyric([]), LyricOwned::None);
+}
+
+#[test]
+fn mpeg_frame_timestamp() {
+    assert_eq!(mpeg_frames_to_duration(100, 44100), Some(ms(2612)));
+    assert_eq!(mpeg_frames_to_duration(100, 0), None);
+}
+
+#[test]
+fn translation_by_language() {
+    let frames = || vec![("chi".to_owned(), "中文"), ("eng".to_owned(), "English")];
+
+    assert_eq!(
+        select_by_language(frames(), Some("zh-CN")),
+        Some(("English", Some("中文")))
+    );
+    assert_eq!(
+        select_by_language(frames(), Some("en_US.UTF-8")),
+        Some(("中文", Some("English")))
+    );
+    assert_eq!(
+        select_by_language(frames(), Some("fi")),
+        Some(("中文", None))
+    );
+    assert_eq!(
+        select_by_language(vec![("chi".to_owned(), "中文")], Some("zh")),
+        Some(("中文", None))
+    );
+    assert_eq!(select_by_language::<()>(vec![], None), None);
+}
+
+#[test]
+fn sylt_frame_content() {
+    let lines = [
+        LyricLineOwned {
+            text: "ab".into(),
+            start_time: ms(1),
+            end_time: None,
+            words: vec![
+                LyricWordOwned {
+                    text: "a".into(),
+                    start_time: ms(1),
+                    end_time: None,
+                },
+                LyricWordOwned {
+                    text: "b".into(),
+                    start_time: ms(2),
+                    end_time: None,
+                },
+            ],
+        },
+        LyricLineOwned {
+            text: "c".into(),
+            start_time: ms(256),
+            ..Default::default()
+        },
+    ];
+
+    assert_eq!(
+        make_sylt(&lines, *b"XXX"),
+        [
+            &[3, b'X', b'X', b'X', 2, 1, 0][..],
+            b"a\0\0\0\0\x01",
+            b"b\0\0\0\0\x02",
+            b"\nc\0\0\0\x01\0",
+        ]
+        .concat()
+    );
+}
@@ -1,3 +1,4 @@
+use crate::lyric_providers::kugou::Kugou;
 use crate::lyric_providers::netease::Netease;
 use crate::lyric_providers::qqmusic::QQMusic;
 use crate::lyric_providers::LyricProvider;
@@ -21,3 +22,11 @@ fn is_netease_songid() {
         assert!(!Netease.is_likely_songid(invalid_songid));
     }
 }
+
+#[test]
+fn is_kugou_songid() {
+    assert!(Kugou.is_likely_songid("0A0C7C3D1F5E9B1C8E2D4F6A8B0C2D4E"));
+    for invalid_songid in ["0A0C7C3D1F5E9B1C8E2D4F6A8B0C2D4", "1145141919", ""] {
+        assert!(!Kugou.is_likely_songid(invalid_songid));
+    }
+}
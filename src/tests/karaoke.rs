@@ -0,0 +1,69 @@
+use std::time::Duration;
+
+use crate::lyric_providers::{LyricLineOwned, LyricWordOwned};
+use crate::sync::lyric::karaoke::{karaoke_markup, sung_len};
+
+fn word(text: &str, start_ms: u64) -> LyricWordOwned {
+    LyricWordOwned {
+        text: text.into(),
+        start_time: Duration::from_millis(start_ms),
+        end_time: None,
+    }
+}
+
+#[test]
+fn linear_interpolation() {
+    let line = LyricLineOwned {
+        text: "abcd".into(),
+        start_time: Duration::from_secs(1),
+        ..Default::default()
+    };
+    let next = Some(Duration::from_secs(5));
+
+    assert_eq!(sung_len(&line, next, Duration::from_millis(500)), 0);
+    assert_eq!(sung_len(&line, next, Duration::from_secs(3)), 2);
+    assert_eq!(sung_len(&line, next, Duration::from_secs(9)), 4);
+    // last line without end is considered sung
+    assert_eq!(sung_len(&line, None, Duration::from_secs(1)), 4);
+}
+
+#[test]
+fn word_timed() {
+    let line = LyricLineOwned {
+        text: "你好 world".into(),
+        start_time: Duration::from_secs(1),
+        words: vec![word("你好 ", 1000), word("world", 2000)],
+    };
+    let next = Some(Duration::from_secs(10));
+
+    // "你好 " is sung, first 2 chars of "world" is sung
+    let sung = sung_len(&line, next, Duration::from_millis(2000 + 3200));
+    assert_eq!(&line.text[..sung], "你好 wo");
+    // half of "你好 "
+    let sung = sung_len(&line, next, Duration::from_millis(1500));
+    assert_eq!(&line.text[..sung], "你");
+}
+
+#[test]
+fn word_with_end_time() {
+    let line = LyricLineOwned {
+        text: "ab".into(),
+        start_time: Duration::from_secs(1),
+        words: vec![LyricWordOwned {
+            end_time: Some(Duration::from_secs(2)),
+            ..word("ab", 1000)
+        }],
+    };
+
+    let sung = sung_len(&line, Some(Duration::from_secs(10)), Duration::from_secs(2));
+    assert_eq!(sung, 2);
+}
+
+#[test]
+fn markup_escaped() {
+    assert_eq!(
+        karaoke_markup("a<b", 1, "#ffffff", 100),
+        r##"<span foreground="#ffffff" fgalpha="100%">a</span>&lt;b"##
+    );
+    assert_eq!(karaoke_markup("a&b", 0, "#ffffff", 100), "a&amp;b");
+}
@@ -0,0 +1,144 @@
+use std::io::Write;
+use std::time::Duration;
+
+use base64::Engine;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+
+use crate::lyric_providers::kugou::krc::{decrypt_krc, parse_krc, KRC_KEY};
+use crate::lyric_providers::kugou::{parse_download_resp, parse_search_resp, Kugou};
+use crate::lyric_providers::{LyricOwned, LyricProvider};
+
+/// translation in line 1, romanization in line 2 for each word
+const LANGUAGE: &str = r#"{"content":[{"language":0,"type":1,"lyricContent":[["你好世界"],[""]]},{"language":0,"type":0,"lyricContent":[["Hel","lo ","world"],["bye"]]}],"version":1}"#;
+
+fn krc_text() -> String {
+    let language = base64::engine::general_purpose::STANDARD.encode(LANGUAGE);
+    format!(
+        "\u{feff}[id:$00000000]\r\n[ar:Someone]\r\n[ti:Hello]\r\n[language:{language}]\r\n\
+         [1000,2000]<0,300,0>Hel<300,200,0>lo <500,1500,0>world\r\n\
+         [4000,500]<0,500,0>Goodbye \r\n"
+    )
+}
+
+fn encrypt_krc(text: &str) -> Vec<u8> {
+    let mut encoder = ZlibEncoder::new(vec![], Compression::default());
+    encoder.write_all(text.as_bytes()).unwrap();
+    let compressed = encoder.finish().unwrap();
+
+    let mut krc = b"krc1".to_vec();
+    krc.extend(
+        compressed
+            .iter()
+            .zip(KRC_KEY.iter().cycle())
+            .map(|(byte, key)| byte ^ key),
+    );
+    krc
+}
+
+#[test]
+fn decrypt_krc_file() {
+    let text = krc_text();
+    assert_eq!(
+        decrypt_krc(&encrypt_krc(&text)).unwrap(),
+        text.trim_start_matches('\u{feff}')
+    );
+    assert!(decrypt_krc(b"krc0").is_err());
+    assert!(decrypt_krc(b"krc1 not zlib").is_err());
+}
+
+#[test]
+fn parse_krc_words() {
+    let krc = parse_krc(&krc_text());
+    let LyricOwned::LineTimestamp(lines) = krc.lyric else {
+        panic!("no lyric in KRC");
+    };
+    assert_eq!(lines.len(), 2);
+
+    let line = &lines[0];
+    assert_eq!(line.text, "Hello world");
+    assert_eq!(line.start_time, Duration::from_millis(1000));
+    assert_eq!(line.end_time, Some(Duration::from_millis(3000)));
+    assert_eq!(line.words[1].text, "lo ");
+    assert_eq!(line.words[1].start_time, Duration::from_millis(1300));
+    assert_eq!(line.words[1].end_time, Some(Duration::from_millis(1500)));
+    assert_eq!(lines[1].text, "Goodbye");
+}
+
+#[test]
+fn parse_krc_languages() {
+    let krc = parse_krc(&krc_text());
+
+    let LyricOwned::LineTimestamp(translation) = krc.translation else {
+        panic!("no translation in KRC");
+    };
+    assert_eq!(translation.len(), 1);
+    assert_eq!(translation[0].text, "你好世界");
+    assert_eq!(translation[0].start_time, Duration::from_millis(1000));
+
+    let LyricOwned::LineTimestamp(romanization) = krc.romanization else {
+        panic!("no romanization in KRC");
+    };
+    assert_eq!(romanization[0].text, "Hello world");
+    assert_eq!(
+        romanization[0].words[2].start_time,
+        Duration::from_millis(1500)
+    );
+    assert_eq!(romanization[1].text, "bye");
+}
+
+// hand-built in the shape of Kugou responses, not recorded from Kugou:
+// the hash, lyric lines and translation in them are made up
+
+/// search response, with a single song
+const SEARCH_RESP: &str = include_str!("kugou/search.json");
+/// lyric download response, with KRC encrypted by [`encrypt_krc`]
+const DOWNLOAD_RESP: &str = include_str!("kugou/download.json");
+
+#[test]
+fn parse_kugou_responses() {
+    let songs = parse_search_resp(SEARCH_RESP).unwrap();
+    assert_eq!(songs.len(), 1);
+    assert_eq!(songs[0].title, "晴天");
+    assert_eq!(songs[0].singer, "周杰伦");
+    assert_eq!(songs[0].album.as_deref(), Some("叶惠美"));
+    assert_eq!(songs[0].length, Duration::from_secs(269));
+    assert!(Kugou.is_likely_songid(&songs[0].id));
+
+    let krc = parse_krc(&parse_download_resp(DOWNLOAD_RESP).unwrap());
+    let LyricOwned::LineTimestamp(lines) = krc.lyric else {
+        panic!("no lyric in KRC");
+    };
+    assert_eq!(lines[0].text, "从前从前");
+    assert_eq!(lines[0].start_time, Duration::from_millis(29264));
+    assert_eq!(lines[1].words[3].start_time, Duration::from_millis(33436));
+    let LyricOwned::LineTimestamp(translation) = krc.translation else {
+        panic!("no translation in KRC");
+    };
+    assert_eq!(translation[0].text, "Once upon a time");
+
+    assert!(parse_search_resp(r#"{"status":0,"data":null}"#).is_err());
+    assert!(parse_download_resp(r#"{"status":404,"content":""}"#).is_err());
+}
+
+/// This test cannot be run from offline environment!
+#[cfg(not(feature = "offline-test"))]
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn get_kugou_lyric() -> anyhow::Result<()> {
+    use crate::lyric_providers::LyricParse;
+
+    let provider = Kugou;
+    let songs = provider
+        .search_song_detailed("", &["周杰伦"], "晴天", None)
+        .await?;
+    let song = songs.first().expect("cannot search in kugou");
+    assert!(provider.is_likely_songid(&song.id));
+
+    let lyric_store = provider.query_lyric(&song.id).await?;
+    let LyricOwned::LineTimestamp(lines) = provider.parse_lyric(&lyric_store) else {
+        anyhow::bail!("cannot get lyric from kugou");
+    };
+    assert!(lines.iter().any(|line| line.is_word_timed()));
+
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use std::time::Duration;
+
+use anyhow::Result;
+
+use crate::lyric_providers::utils::lrc_iter;
+use crate::lyric_providers::{LyricLine, LyricLineOwned};
+
+#[test]
+fn end_time_from_trailing_word_tag() -> Result<()> {
+    let lrc = "[00:01.00]<00:01.00>Hi <00:01.50>there<00:02.50>\n[00:40.00]again";
+    let lyrics = lrc_iter(lrc.lines())?
+        .into_iter()
+        .map(LyricLine::into_owned)
+        .collect::<Vec<_>>();
+
+    assert_eq!(lyrics[0].end_time, Some(Duration::from_millis(2500)));
+    assert_eq!(lyrics[1].end_time, None);
+    Ok(())
+}
+
+#[test]
+fn line_over_by_end_time() {
+    let line = LyricLineOwned {
+        text: "Hi".into(),
+        start_time: Duration::from_secs(1),
+        end_time: Some(Duration::from_secs(3)),
+        ..Default::default()
+    };
+
+    assert!(!line.is_over(Duration::from_secs(2), Duration::ZERO));
+    assert!(line.is_over(Duration::from_secs(3), Duration::ZERO));
+    // known end time wins over max hold
+    assert!(!line.is_over(Duration::from_secs(2), Duration::from_millis(500)));
+}
+
+#[test]
+fn line_over_by_max_hold() {
+    let line = LyricLineOwned {
+        text: "Hi".into(),
+        start_time: Duration::from_secs(1),
+        ..Default::default()
+    };
+
+    assert!(!line.is_over(Duration::from_secs(20), Duration::from_secs(20)));
+    assert!(line.is_over(Duration::from_secs(21), Duration::from_secs(20)));
+    // zero max hold disables the heuristic
+    assert!(!line.is_over(Duration::from_secs(3600), Duration::ZERO));
+}
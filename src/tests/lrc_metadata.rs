@@ -0,0 +1,64 @@
+use std::time::Duration;
+
+use anyhow::Result;
+
+use crate::lyric_providers::utils::{lrc_iter_with_metadata, lrc_metadata, LrcMetadata};
+use crate::sync::{check_lrc_metadata, LrcMismatch, TrackMeta};
+
+const LRC: &str = "[ti:晴天]
+[ar:周杰伦]
+[al:叶惠美]
+[length: 04:29.5]
+[offset:+350]
+[00:01.00]故事的小黄花";
+
+#[test]
+fn parse_metadata() -> Result<()> {
+    let (lines, metadata) = lrc_iter_with_metadata(LRC.lines())?;
+
+    assert_eq!(lines.len(), 1);
+    assert_eq!(
+        metadata,
+        LrcMetadata {
+            title: Some("晴天".into()),
+            artist: Some("周杰伦".into()),
+            album: Some("叶惠美".into()),
+            length: Some(Duration::from_millis(269_500)),
+            offset_ms: 350,
+        }
+    );
+    // positive `[offset:]` makes lyric show sooner
+    assert_eq!(metadata.lyric_offset_ms(), -350);
+    Ok(())
+}
+
+#[test]
+fn malformed_tags_ignored() {
+    let metadata = lrc_metadata("[offset:abc]\n[length:4m]\n[00:01.00]text\n[ti]".lines());
+    assert_eq!(metadata, LrcMetadata::default());
+}
+
+#[test]
+fn check_against_track() {
+    let metadata = lrc_metadata(LRC.lines());
+    let mut track = TrackMeta {
+        unique_song_id: None,
+        title: Some("晴天 (Live)".into()),
+        album: None,
+        artists: Some(vec!["周杰伦".into()]),
+        length: Some(Duration::from_secs(270)),
+    };
+    assert_eq!(check_lrc_metadata(&metadata, &track, 2000), Ok(()));
+
+    track.length = Some(Duration::from_secs(200));
+    assert_eq!(
+        check_lrc_metadata(&metadata, &track, 2000),
+        Err(LrcMismatch::Length(Duration::from_millis(269_500)))
+    );
+
+    track.title = Some("稻香".into());
+    assert_eq!(
+        check_lrc_metadata(&metadata, &track, 2000),
+        Err(LrcMismatch::Title("晴天".into()))
+    );
+}
@@ -3,7 +3,7 @@
 fn make_lrc_line() {
     use std::time::Duration;
 
-    use crate::sync::actions::utils::make_lrc_line;
+    use crate::lyric_providers::utils::make_lrc_line;
 
     assert_eq!(
         make_lrc_line("你好", Duration::from_secs(61)),
@@ -0,0 +1,85 @@
+use std::time::Duration;
+
+use crate::lyric_providers::utils::format_lrc_time;
+use crate::lyric_providers::{LyricLineOwned, LyricWordOwned};
+use crate::sync::lyric::edit::{pair_lines, parse_timestamp, unpair_lines, EditedLine};
+use crate::tests::line;
+
+#[test]
+fn pair_translation_with_shown_line() {
+    let origin = [line("Hello", 1000), line("world", 3000)];
+    let translation = [line("你好", 1000), line("世界", 3200), line("！", 3500)];
+
+    let lines = pair_lines(&origin, &translation);
+    assert_eq!(
+        lines,
+        [
+            EditedLine {
+                line: line("Hello", 1000),
+                translation: Some(line("你好", 1000)),
+            },
+            EditedLine {
+                line: line("world", 3000),
+                translation: Some(line("世界", 3200)),
+            },
+        ]
+    );
+}
+
+#[test]
+fn edit_and_unpair() {
+    let mut lines = pair_lines(
+        &[line("Hello", 1000), line("world", 3000)],
+        &[line("你好", 1000)],
+    );
+
+    lines[0].set_start_time(Duration::from_millis(4000));
+    lines[0].set_translation("");
+    lines[1].set_translation("世界");
+    lines[1].shift(-500);
+
+    let (origin, translation) = unpair_lines(lines);
+    assert_eq!(origin, [line("world", 2500), line("Hello", 4000)]);
+    assert_eq!(translation, [line("世界", 2500)]);
+}
+
+#[test]
+fn shift_word_timings() {
+    let word = |text: &str, start_ms| LyricWordOwned {
+        text: text.into(),
+        start_time: Duration::from_millis(start_ms),
+        end_time: None,
+    };
+    let mut edited = EditedLine {
+        line: LyricLineOwned {
+            words: vec![word("Hel", 1000), word("lo", 1500)],
+            ..line("Hello", 1000)
+        },
+        translation: None,
+    };
+
+    edited.shift(200);
+    assert_eq!(edited.line.words, [word("Hel", 1200), word("lo", 1700)]);
+    edited.shift(-5000);
+    assert_eq!(edited.line.start_time, Duration::ZERO);
+
+    edited.set_text("Hello!");
+    assert!(edited.line.words.is_empty());
+}
+
+#[test]
+fn timestamp_text() {
+    assert_eq!(format_lrc_time(Duration::from_millis(83_045)), "01:23.045");
+    assert_eq!(
+        parse_timestamp("01:23.045"),
+        Some(Duration::from_millis(83_045))
+    );
+    assert_eq!(
+        parse_timestamp(" 1:23.4 "),
+        Some(Duration::from_millis(83_400))
+    );
+    assert_eq!(parse_timestamp("1:23"), Some(Duration::from_secs(83)));
+    assert_eq!(parse_timestamp("1:60"), None);
+    assert_eq!(parse_timestamp("1:23.4567"), None);
+    assert_eq!(parse_timestamp("abc"), None);
+}
@@ -0,0 +1,60 @@
+use encoding_rs::{BIG5, GBK, SHIFT_JIS};
+
+use crate::lyric_providers::encoding::{decode_lyric, encodings_from_labels, DecodeError};
+
+const LRC: &str = "[00:01.00]春眠不觉晓\n[00:02.00]处处闻啼鸟\n";
+
+#[test]
+fn decode_utf8() {
+    assert_eq!(decode_lyric(LRC.as_bytes(), &[]).unwrap(), LRC);
+}
+
+#[test]
+fn decode_by_bom() {
+    let mut utf16 = vec![0xff, 0xfe];
+    for unit in LRC.encode_utf16() {
+        utf16.extend(unit.to_le_bytes());
+    }
+    assert_eq!(decode_lyric(&utf16, &[]).unwrap(), LRC);
+
+    // odd length is never valid UTF-16
+    assert!(matches!(
+        decode_lyric(&utf16[..5], &[]),
+        Err(DecodeError::Malformed("UTF-16LE"))
+    ));
+
+    let utf8 = [b"\xef\xbb\xbf".as_slice(), LRC.as_bytes()].concat();
+    assert_eq!(decode_lyric(&utf8, &[]).unwrap(), LRC);
+}
+
+#[test]
+fn guess_legacy_encodings() {
+    let lyrics = [
+        (
+            GBK,
+            "[00:01.00]春眠不觉晓，处处闻啼鸟。夜来风雨声，花落知多少。\n",
+        ),
+        (
+            BIG5,
+            "[00:01.00]春眠不覺曉，處處聞啼鳥。夜來風雨聲，花落知多少。\n",
+        ),
+        (
+            SHIFT_JIS,
+            "[00:01.00]古池や蛙飛び込む水の音。静けさや岩にしみ入る蝉の声。\n",
+        ),
+    ];
+    for (encoding, lyric) in lyrics {
+        let (bytes, _, _) = encoding.encode(lyric);
+        assert_eq!(decode_lyric(&bytes, &[]).unwrap(), lyric);
+    }
+}
+
+#[test]
+fn configured_encodings_go_first() {
+    let lyric = "[00:01.00]ｱｲｳ\n";
+    let (bytes, _, _) = SHIFT_JIS.encode(lyric);
+
+    let encodings = encodings_from_labels(&["shift_jis".into(), "no-such-encoding".into()]);
+    assert_eq!(encodings, [SHIFT_JIS]);
+    assert_eq!(decode_lyric(&bytes, &encodings).unwrap(), lyric);
+}
@@ -0,0 +1,101 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::lyric_providers::library::{
+    match_filename, parse_library_lyric, FilenameFields, LibraryIndex, LyricLibrary,
+    LyricLibraryConfig,
+};
+use crate::lyric_providers::{LyricOwned, LyricParse, LyricProvider};
+
+#[test]
+fn match_filename_pattern() {
+    assert_eq!(
+        match_filename("{artist} - {title}", "A - B - C"),
+        Some(FilenameFields {
+            title: Some("B - C".into()),
+            artist: Some("A".into()),
+            album: None,
+        })
+    );
+    assert_eq!(
+        match_filename("{track}. {title}", "01. Hello")
+            .unwrap()
+            .title,
+        Some("Hello".into())
+    );
+    assert_eq!(match_filename("{artist} - {title}", "Hello"), None);
+    assert_eq!(match_filename("{artist} - {title}", " - Hello"), None);
+}
+
+fn library_dir() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("waylyrics-library-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("nested")).unwrap();
+
+    fs::write(
+        dir.join("whatever.lrc"),
+        "[ti:Hello]\n[ar:Someone]\n[length:03:20]\n[00:01.00]Hello",
+    )
+    .unwrap();
+    fs::write(dir.join("nested/Other - Hello World.srt"), "").unwrap();
+    fs::write(dir.join("nested/Goodbye.ttml"), "").unwrap();
+    fs::write(dir.join("nested/Someone - Hello.mp3"), "").unwrap();
+    dir
+}
+
+#[test]
+fn search_library() {
+    let dir = library_dir();
+    let index = LibraryIndex::build(&LyricLibraryConfig {
+        dirs: vec![dir.clone()],
+        ..Default::default()
+    });
+    fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(index.entries.len(), 3);
+
+    let songs = index.search(&["someone"], "Hello");
+    assert_eq!(songs.len(), 1);
+    assert_eq!(songs[0].title, "Hello");
+    assert_eq!(songs[0].singer, "Someone");
+    assert_eq!(songs[0].length, Duration::from_secs(200));
+    assert!(songs[0].id.ends_with("whatever.lrc"));
+
+    let titles: Vec<_> = index
+        .search(&[], "hello")
+        .into_iter()
+        .map(|song| song.title)
+        .collect();
+    assert_eq!(titles, ["Hello", "Hello World"]);
+
+    let songs = index.search_keyword("goodbye");
+    assert_eq!(songs.len(), 1);
+    assert_eq!(songs[0].singer, "");
+}
+
+#[test]
+fn parse_library_lyric_by_content() {
+    let (lyric, _) = parse_library_lyric(Some("1\n00:00:01,000 --> 00:00:02,000\nHello\n"));
+    let LyricOwned::LineTimestamp(lines) = lyric else {
+        panic!("subtitle is not parsed");
+    };
+    assert_eq!(lines[0].start_time, Duration::from_secs(1));
+
+    let (lyric, _) = parse_library_lyric(Some("[00:01.00]Hello"));
+    assert!(lyric.is_line_timestamp());
+
+    let (lyric, translation) = parse_library_lyric(None);
+    assert!(lyric.is_none() && translation.is_none());
+}
+
+#[tokio::test]
+async fn library_lyric_offset() -> anyhow::Result<()> {
+    let path = std::env::temp_dir().join(format!("waylyrics-offset-{}.lrc", std::process::id()));
+    fs::write(&path, "[offset:500]\n[00:01.00]Hello")?;
+    let store = LyricLibrary.query_lyric(path.to_str().unwrap()).await;
+    fs::remove_file(&path)?;
+
+    let metadata = LyricLibrary.parse_lyric_metadata(&store?);
+    assert_eq!(metadata.lyric_offset_ms(), -500);
+    Ok(())
+}
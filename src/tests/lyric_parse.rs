@@ -20,6 +20,7 @@ mod lrc {
             Some(LyricLineOwned {
                 text: "Hi friend...".into(),
                 start_time: Duration::from_millis(1000 + 50),
+                ..Default::default()
             })
         );
         assert_eq!(
@@ -27,6 +28,7 @@ mod lrc {
             Some(LyricLineOwned {
                 text: "Can you hear me?...".into(),
                 start_time: Duration::from_millis(640 + (45 * 60 + 5) * 1000),
+                ..Default::default()
             })
         );
 
@@ -46,6 +48,7 @@ mod lrc {
             Some(LyricLineOwned {
                 text: "Hi friend...".into(),
                 start_time: Duration::from_millis(1000 + 50),
+                ..Default::default()
             })
         );
         assert_eq!(
@@ -53,6 +56,7 @@ mod lrc {
             Some(LyricLineOwned {
                 text: "Can you hear me?...".into(),
                 start_time: Duration::from_millis(640 + (45 * 60 + 5) * 1000),
+                ..Default::default()
             })
         );
 
@@ -71,6 +75,7 @@ mod lrc {
             Some(LyricLineOwned {
                 text: "Hi friend...".into(),
                 start_time: Duration::from_millis(1000 + 50),
+                ..Default::default()
             })
         );
         Ok(())
@@ -90,6 +95,7 @@ Author: poly000
             Some(LyricLineOwned {
                 text: "Hi friend...".into(),
                 start_time: Duration::from_millis(1000 + 50),
+                ..Default::default()
             })
         );
         Ok(())
@@ -117,6 +123,7 @@ Author: poly000
             Some(LyricLineOwned {
                 text: "Hi friend...".into(),
                 start_time: Duration::from_millis(1014),
+                ..Default::default()
             })
         );
 
@@ -125,6 +132,7 @@ Author: poly000
             Some(LyricLineOwned {
                 text: "Hi friend...".into(),
                 start_time: Duration::from_millis(2062),
+                ..Default::default()
             })
         );
 
@@ -144,6 +152,7 @@ Author: poly000
             Some(LyricLineOwned {
                 text: "Hi friend...".into(),
                 start_time: Duration::from_millis(1014),
+                ..Default::default()
             })
         );
 
@@ -152,6 +161,7 @@ Author: poly000
             Some(LyricLineOwned {
                 text: "".into(),
                 start_time: Duration::from_millis(2062),
+                ..Default::default()
             })
         );
 
@@ -174,7 +184,8 @@ Author: poly000
             tlyric,
             vec![LyricLineOwned {
                 text: "请不要说“你很懒”".into(),
-                start_time: Duration::from_millis(1770)
+                start_time: Duration::from_millis(1770),
+                ..Default::default()
             },]
         );
         assert_eq!(
@@ -182,11 +193,13 @@ Author: poly000
             vec![
                 LyricLineOwned {
                     text: "Please don't say \"You are lazy\"".into(),
-                    start_time: Duration::from_millis(1770)
+                    start_time: Duration::from_millis(1770),
+                    ..Default::default()
                 },
                 LyricLineOwned {
                     text: "だって本当はcrazy".into(),
-                    start_time: Duration::from_millis(4380)
+                    start_time: Duration::from_millis(4380),
+                    ..Default::default()
                 }
             ]
         );
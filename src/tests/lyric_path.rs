@@ -38,3 +38,19 @@ fn empty_string() {
     let result = get_lrc_path(PathBuf::default());
     assert_eq!(result, None)
 }
+
+#[test]
+fn existing_sidecar_lyric() {
+    let dir = std::env::temp_dir().join(format!("waylyrics-lyric-path-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("test.ttml"), "").unwrap();
+
+    let result = get_lrc_path(dir.join("test.mp3"));
+    assert_eq!(result, Some(dir.join("test.ttml")));
+
+    std::fs::write(dir.join("test.lrc"), "").unwrap();
+    let result = get_lrc_path(dir.join("test.mp3"));
+    assert_eq!(result, Some(dir.join("test.lrc")));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
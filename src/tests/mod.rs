@@ -1,8 +1,45 @@
+#[cfg(feature = "export-lyric")]
+mod bilingual_lrc;
+#[cfg(unix)]
+mod command_provider;
+mod context_lines;
+mod display_mode;
+mod http_provider;
+mod id3_lyric;
 mod is_likely_songid;
+mod karaoke;
+mod kugou_lyric;
+mod line_end_time;
+mod lrc_metadata;
 mod lrclib_lyric;
 mod lyric_construct;
+mod lyric_edit;
+mod lyric_encoding;
+mod lyric_library;
 mod lyric_parse;
 mod lyric_path;
 mod netease_lyric;
+mod netease_yrc;
+mod provider_registry;
 mod qqmusic_init;
 mod strip_extended_timestamps;
+mod subtitle_lyric;
+mod sync_now;
+mod tap_sync;
+mod time_stretch;
+mod ttml_lyric;
+mod unsynced_lyric;
+mod word_timestamp;
+
+use std::time::Duration;
+
+use crate::lyric_providers::LyricLineOwned;
+
+/// a line timed by its start only
+pub(crate) fn line(text: &str, start_ms: u64) -> LyricLineOwned {
+    LyricLineOwned {
+        text: text.into(),
+        start_time: Duration::from_millis(start_ms),
+        ..Default::default()
+    }
+}
@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+use crate::lyric_providers::netease::yrc::parse_yrc;
+use crate::lyric_providers::LyricOwned;
+
+const YRC: &str = r#"{"t":0,"c":[{"tx":"作词: "},{"tx":"Someone"}]}
+[4000,1000](4000,1000,0)Goodbye
+[1000,2000](1000,300,0)Hel(1300,200,0)lo (1500,1500,0)world
+[6000,1500](6000,500,0)(oh) (6500,1000,0)yeah
+"#;
+
+#[test]
+fn parse_yrc_words() {
+    let LyricOwned::LineTimestamp(lines) = parse_yrc(YRC) else {
+        panic!("no lyric in YRC");
+    };
+    assert_eq!(lines.len(), 3);
+
+    let line = &lines[0];
+    assert_eq!(line.text, "Hello world");
+    assert_eq!(line.start_time, Duration::from_millis(1000));
+    assert_eq!(line.end_time, Some(Duration::from_millis(3000)));
+    assert_eq!(line.words.len(), 3);
+    assert_eq!(line.words[1].text, "lo ");
+    assert_eq!(line.words[1].start_time, Duration::from_millis(1300));
+    assert_eq!(line.words[1].end_time, Some(Duration::from_millis(1500)));
+    assert_eq!(lines[1].text, "Goodbye");
+}
+
+#[test]
+fn parse_yrc_parentheses_in_text() {
+    let LyricOwned::LineTimestamp(lines) = parse_yrc(YRC) else {
+        panic!("no lyric in YRC");
+    };
+    let line = &lines[2];
+    assert_eq!(line.text, "(oh) yeah");
+    assert_eq!(line.words[0].text, "(oh) ");
+    assert_eq!(line.words[1].start_time, Duration::from_millis(6500));
+}
+
+#[test]
+fn parse_yrc_credits_only() {
+    let credits = r#"{"t":0,"c":[{"tx":"作曲: "},{"tx":"Someone"}]}"#;
+    assert!(matches!(parse_yrc(credits), LyricOwned::None));
+    assert!(matches!(parse_yrc(""), LyricOwned::None));
+}
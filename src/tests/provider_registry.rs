@@ -0,0 +1,48 @@
+use crate::lyric_providers::registry::{get_provider, setup_providers, RegistryError, PROVIDERS};
+use crate::lyric_providers::Capabilities;
+
+#[test]
+fn ids_are_unique_ascii() {
+    for (index, provider) in PROVIDERS.iter().enumerate() {
+        let id = provider.id();
+        assert!(!id.is_empty() && id.bytes().all(|b| b.is_ascii_lowercase()));
+        assert!(PROVIDERS[index + 1..].iter().all(|other| other.id() != id));
+    }
+}
+
+#[test]
+fn look_up_by_id_or_display_name() {
+    assert_eq!(get_provider("netease").unwrap().unique_name(), "网易云音乐");
+    assert_eq!(get_provider("网易云音乐").unwrap().id(), "netease");
+    assert_eq!(get_provider(" lrclib ").unwrap().id(), "lrclib");
+    assert!(get_provider("kuwo").is_none());
+}
+
+#[test]
+fn setup_in_order() {
+    let names = ["lrclib".to_owned(), "netease".into(), "LRCLib".into()];
+    let providers = setup_providers(&names, &serde_json::json!({})).unwrap();
+
+    let ids: Vec<_> = providers.iter().map(|provider| provider.id()).collect();
+    assert_eq!(ids, ["lrclib", "netease"]);
+}
+
+#[test]
+fn unknown_provider_is_error() {
+    let names = ["netease".to_owned(), "kuwo".into()];
+    let error = setup_providers(&names, &serde_json::json!({})).unwrap_err();
+
+    assert!(matches!(&error, RegistryError::Unknown(name) if name == "kuwo"));
+    assert!(error.to_string().contains("netease, qqmusic, lrclib"));
+}
+
+#[test]
+fn format_capabilities() {
+    assert_eq!(Capabilities::default().to_string(), "lyric only");
+    let capabilities = Capabilities {
+        translation: true,
+        word_timing: false,
+        romanization: true,
+    };
+    assert_eq!(capabilities.to_string(), "translation, romanization");
+}
@@ -0,0 +1,111 @@
+use std::time::Duration;
+
+use anyhow::Result;
+
+use crate::lyric_providers::subtitle::{
+    make_srt, make_vtt, parse_subtitle, parse_subtitle_time, SubtitleError,
+};
+use crate::lyric_providers::{LyricLineOwned, LyricWordOwned};
+
+fn line(text: &str, start_ms: u64, end_ms: Option<u64>) -> LyricLineOwned {
+    LyricLineOwned {
+        text: text.into(),
+        start_time: Duration::from_millis(start_ms),
+        end_time: end_ms.map(Duration::from_millis),
+        words: vec![],
+    }
+}
+
+#[test]
+fn parse_srt() -> Result<()> {
+    let srt = "1\r\n00:00:04,000 --> 00:00:05,000\r\nA &amp; B\r\n\r\n\
+               2\r\n00:00:01,000 --> 00:00:03,500\r\n<i>Hello</i>\r\nworld {\\an8}\r\n";
+    let lines = parse_subtitle(srt)?;
+
+    assert_eq!(
+        lines,
+        [
+            line("Hello world", 1000, Some(3500)),
+            line("A & B", 4000, Some(5000)),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn parse_vtt_word_timing() -> Result<()> {
+    let vtt = "WEBVTT\n\nNOTE made by hand\n\nintro\n\
+               00:01.000 --> 00:03.000 align:start\n\
+               <v Bob>Hello <00:01.500>world<00:02.500>!</v>\n";
+    let lines = parse_subtitle(vtt)?;
+
+    let word = |text: &str, start_ms, end_ms: Option<u64>| LyricWordOwned {
+        text: text.into(),
+        start_time: Duration::from_millis(start_ms),
+        end_time: end_ms.map(Duration::from_millis),
+    };
+    assert_eq!(
+        lines,
+        [LyricLineOwned {
+            words: vec![
+                word("Hello ", 1000, None),
+                word("world", 1500, None),
+                word("!", 2500, Some(3000)),
+            ],
+            ..line("Hello world!", 1000, Some(3000))
+        }]
+    );
+    Ok(())
+}
+
+#[test]
+fn invalid_timing() {
+    assert!(matches!(
+        parse_subtitle("00:01 --> 00:02\nno milliseconds"),
+        Err(SubtitleError::Timing(_))
+    ));
+}
+
+#[test]
+fn subtitle_time() {
+    assert_eq!(
+        parse_subtitle_time("01:02:03,456"),
+        Some(Duration::from_millis(3_723_456))
+    );
+    assert_eq!(
+        parse_subtitle_time("02:03.4"),
+        Some(Duration::from_millis(123_400))
+    );
+    assert_eq!(parse_subtitle_time("3.000"), None);
+    assert_eq!(parse_subtitle_time("aa:03.000"), None);
+}
+
+#[test]
+fn export_srt() {
+    let lines = [
+        line("first", 1000, None),
+        line("", 2000, None),
+        line("second", 3000, Some(4500)),
+        line("last", 5000, None),
+    ];
+
+    assert_eq!(
+        make_srt(&lines),
+        "1\n00:00:01,000 --> 00:00:02,000\nfirst\n\n\
+         2\n00:00:03,000 --> 00:00:04,500\nsecond\n\n\
+         3\n00:00:05,000 --> 00:00:10,000\nlast\n\n"
+    );
+}
+
+#[test]
+fn vtt_roundtrip() -> Result<()> {
+    let lines = vec![
+        line("<one> & two", 1000, Some(2000)),
+        line("three", 62_000, Some(3_725_000)),
+    ];
+
+    let vtt = make_vtt(&lines);
+    assert!(vtt.starts_with("WEBVTT\n\n00:00:01.000 --> 00:00:02.000\n&lt;one&gt; &amp; two\n"));
+    assert_eq!(parse_subtitle(&vtt)?, lines);
+    Ok(())
+}
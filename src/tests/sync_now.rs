@@ -0,0 +1,39 @@
+use std::time::Duration;
+
+use crate::sync::actions::utils::calibrate_lyric_offset;
+use crate::tests::line;
+
+#[test]
+fn calibrate_with_shown_line() {
+    let lines = [line("Hello", 1000), line("", 2000), line("world", 3000)];
+
+    // lyric is 300ms early: "world" is shown when it's heard at 3300ms
+    let elapsed = Duration::from_millis(3300);
+    assert_eq!(calibrate_lyric_offset(&lines, elapsed, 0, false), Some(300));
+    // blank lines are skipped
+    let elapsed = Duration::from_millis(2500);
+    assert_eq!(
+        calibrate_lyric_offset(&lines, elapsed, 100, false),
+        Some(1600)
+    );
+    assert_eq!(
+        calibrate_lyric_offset(&lines, Duration::from_millis(500), 0, false),
+        None
+    );
+}
+
+#[test]
+fn calibrate_with_next_line() {
+    let lines = [line("Hello", 1000), line("", 2000), line("world", 3000)];
+
+    // lyric is 500ms late: "world" is heard while "Hello" is still shown
+    let elapsed = Duration::from_millis(2500);
+    assert_eq!(
+        calibrate_lyric_offset(&lines, elapsed, 200, true),
+        Some(-300)
+    );
+    assert_eq!(
+        calibrate_lyric_offset(&lines, Duration::from_millis(3500), 0, true),
+        None
+    );
+}
@@ -0,0 +1,71 @@
+use std::time::Duration;
+
+use crate::lyric_providers::LyricOwned;
+use crate::sync::lyric::tap::{retime_paired_lines, TapSync};
+use crate::tests::line;
+
+#[test]
+fn tap_lines() {
+    let mut tap_sync = TapSync::new("  Hello\n\nworld\r\nagain\n");
+    assert_eq!(tap_sync.len(), 3);
+    assert_eq!(tap_sync.current_line(), Some("Hello"));
+    assert_eq!(tap_sync.next_line(), Some("world"));
+
+    assert!(tap_sync.tap(Duration::from_millis(1000)));
+    assert!(tap_sync.tap(Duration::from_millis(2500)));
+    tap_sync.undo();
+    assert_eq!(tap_sync.current_line(), Some("world"));
+    assert!(tap_sync.tap(Duration::from_millis(2000)));
+    assert!(tap_sync.tap(Duration::from_millis(3000)));
+
+    assert!(tap_sync.is_finished());
+    assert_eq!(tap_sync.current_line(), None);
+    assert!(!tap_sync.tap(Duration::from_millis(4000)));
+
+    assert_eq!(
+        tap_sync.into_lyric(),
+        LyricOwned::LineTimestamp(vec![
+            line("Hello", 1000),
+            line("world", 2000),
+            line("again", 3000),
+        ])
+    );
+}
+
+#[test]
+fn untapped_lines_are_dropped() {
+    let mut tap_sync = TapSync::new("Hello\nworld");
+    assert_eq!(tap_sync.clone().into_lyric(), LyricOwned::None);
+
+    assert!(tap_sync.tap(Duration::from_millis(5000)));
+    // seeked back before tapping the second line
+    assert!(!tap_sync.tap(Duration::from_millis(1000)));
+    assert_eq!(tap_sync.current_line(), Some("world"));
+    assert_eq!(
+        tap_sync.into_lyric(),
+        LyricOwned::LineTimestamp(vec![line("Hello", 5000)])
+    );
+
+    assert!(TapSync::new(" \n").is_empty());
+}
+
+#[test]
+fn retime_translation() {
+    let old = [line("Hello", 1000), line("", 2000), line("world", 3000)];
+    let translation = [line("你好", 1000), line("世界", 3000)];
+    let new = [line("Hello", 1500), line("world", 3500)];
+    assert_eq!(
+        retime_paired_lines(&old, &translation, &new),
+        Some(vec![line("你好", 1500), line("世界", 3500)])
+    );
+
+    // only the tapped lines are kept
+    assert_eq!(
+        retime_paired_lines(&old, &translation, &new[..1]),
+        Some(vec![line("你好", 1500)])
+    );
+
+    let changed = [line("Hello", 1500), line("there", 3500)];
+    assert_eq!(retime_paired_lines(&old, &translation, &changed), None);
+    assert_eq!(retime_paired_lines(&[], &translation, &new), None);
+}
@@ -0,0 +1,120 @@
+use std::time::Duration;
+
+use crate::lyric_providers::{LyricLineOwned, LyricWordOwned};
+use crate::sync::lyric::stretch::{SyncPoint, TimeStretch};
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+#[test]
+fn fit_track_length() {
+    let max_diff = Duration::from_secs(5);
+
+    // lyric of a 200s song drifts 3s by the end of a 203s track
+    let stretch = TimeStretch::fit_length(ms(200_000), ms(203_000), max_diff).unwrap();
+    assert_eq!(stretch.offset_ms, 0);
+    assert_eq!(stretch.apply(ms(200_000)), ms(203_000));
+    assert_eq!(stretch.apply(ms(100_000)), ms(101_500));
+
+    assert_eq!(
+        TimeStretch::fit_length(ms(200_000), ms(200_000), max_diff),
+        None
+    );
+    assert_eq!(
+        TimeStretch::fit_length(ms(200_000), ms(206_000), max_diff),
+        None
+    );
+    assert_eq!(
+        TimeStretch::fit_length(Duration::ZERO, ms(3_000), max_diff),
+        None
+    );
+}
+
+#[test]
+fn solve_from_sync_points() {
+    // lines are heard 1s late at 10s, and 3s late at 110s
+    let first = SyncPoint {
+        lyric_time: ms(10_000),
+        position: ms(11_000),
+    };
+    let second = SyncPoint {
+        lyric_time: ms(110_000),
+        position: ms(113_000),
+    };
+
+    let stretch = TimeStretch::from_sync_points(first, second, 0).unwrap();
+    assert_eq!(stretch.apply(first.lyric_time), first.position);
+    assert_eq!(stretch.apply(second.lyric_time), second.position);
+
+    // lyric offset of 500ms is taken into account
+    let stretch = TimeStretch::from_sync_points(second, first, 500).unwrap();
+    assert_eq!(stretch.apply(first.lyric_time), ms(10_500));
+    assert_eq!(stretch.apply(second.lyric_time), ms(112_500));
+}
+
+#[test]
+fn reject_bad_sync_points() {
+    let point = |lyric_ms, position_ms| SyncPoint {
+        lyric_time: ms(lyric_ms),
+        position: ms(position_ms),
+    };
+
+    // too close
+    assert_eq!(
+        TimeStretch::from_sync_points(point(10_000, 10_000), point(15_000, 16_000), 0),
+        None
+    );
+    // the second line heard before the first one
+    assert_eq!(
+        TimeStretch::from_sync_points(point(10_000, 30_000), point(60_000, 20_000), 0),
+        None
+    );
+}
+
+#[test]
+fn lyric_time_reverses_stretch() {
+    let stretch = TimeStretch {
+        scale: 1.015,
+        offset_ms: -200,
+    };
+    for time in [ms(500), ms(1_234), ms(61_000), ms(180_500)] {
+        let shown = stretch.apply(time);
+        assert!(stretch.lyric_time(shown).abs_diff(time) <= ms(1));
+    }
+    assert_eq!(stretch.apply(ms(100)), Duration::ZERO);
+    assert!(TimeStretch::default().is_identity());
+}
+
+#[test]
+fn stretch_line_with_words() {
+    let stretch = TimeStretch {
+        scale: 2.,
+        offset_ms: 100,
+    };
+    let mut line = LyricLineOwned {
+        text: "Hello world".into(),
+        start_time: ms(1_000),
+        end_time: Some(ms(2_000)),
+        words: vec![
+            LyricWordOwned {
+                text: "Hello ".into(),
+                start_time: ms(1_000),
+                end_time: None,
+            },
+            LyricWordOwned {
+                text: "world".into(),
+                start_time: ms(1_500),
+                end_time: Some(ms(2_000)),
+            },
+        ],
+    };
+    stretch.apply_to_line(&mut line);
+
+    assert_eq!(line.start_time, ms(2_100));
+    assert_eq!(line.end_time, Some(ms(4_100)));
+    assert_eq!(line.words[0].start_time, ms(2_100));
+    assert_eq!(line.words[0].end_time, None);
+    assert_eq!(line.words[1].start_time, ms(3_100));
+    assert_eq!(line.words[1].end_time, Some(ms(4_100)));
+}
@@ -0,0 +1,143 @@
+use std::time::Duration;
+
+use anyhow::Result;
+
+use crate::lyric_providers::ttml::{make_ttml, parse_ttml, parse_ttml_time, TtmlError};
+use crate::lyric_providers::{LyricLineOwned, LyricOwned, LyricWordOwned};
+
+const WORD_TIMED: &str = r#"<tt xmlns="http://www.w3.org/ns/ttml" xmlns:itunes="http://music.apple.com/lyric-ttml-internal" xmlns:ttm="http://www.w3.org/ns/ttml#metadata" itunes:timing="Word">
+  <head><metadata>
+    <ttm:agent type="person" xml:id="v1"/>
+    <iTunesMetadata xmlns="http://music.apple.com/lyric-ttml-internal">
+      <translations><translation type="subtitle" xml:lang="zh-Hans">
+        <text for="L1">你好世界</text>
+      </translation></translations>
+    </iTunesMetadata>
+  </metadata></head>
+  <body><div>
+    <p begin="0:01.000" end="0:03.000" itunes:key="L1" ttm:agent="v1">
+      <span begin="0:01.000" end="0:02.000">Hello</span>
+      <span begin="0:02.000" end="0:03.000">world</span>
+      <span ttm:role="x-bg"><span begin="0:02.500" end="0:03.200">(world)</span></span>
+    </p>
+  </div></body>
+</tt>"#;
+
+fn word(text: &str, start_ms: u64, end_ms: u64) -> LyricWordOwned {
+    LyricWordOwned {
+        text: text.into(),
+        start_time: Duration::from_millis(start_ms),
+        end_time: Some(Duration::from_millis(end_ms)),
+    }
+}
+
+#[test]
+fn word_timed_with_background() -> Result<()> {
+    let (origin, translation) = parse_ttml(WORD_TIMED)?;
+
+    assert_eq!(
+        origin,
+        LyricOwned::LineTimestamp(vec![LyricLineOwned {
+            text: "Hello world (world)".into(),
+            start_time: Duration::from_secs(1),
+            end_time: Some(Duration::from_millis(3200)),
+            words: vec![
+                word("Hello ", 1000, 2000),
+                word("world ", 2000, 3000),
+                word("(world)", 2500, 3200),
+            ],
+        }])
+    );
+    assert_eq!(
+        translation,
+        LyricOwned::LineTimestamp(vec![LyricLineOwned {
+            text: "你好世界".into(),
+            start_time: Duration::from_secs(1),
+            end_time: Some(Duration::from_millis(3200)),
+            words: vec![],
+        }])
+    );
+    Ok(())
+}
+
+#[test]
+fn duet_agents() -> Result<()> {
+    let ttml = r#"<tt xmlns="http://www.w3.org/ns/ttml" xmlns:ttm="http://www.w3.org/ns/ttml#metadata">
+  <head><metadata>
+    <ttm:agent type="person" xml:id="v1"><ttm:name type="full">Alice</ttm:name></ttm:agent>
+    <ttm:agent type="person" xml:id="v2"><ttm:name type="full">Bob</ttm:name></ttm:agent>
+  </metadata></head>
+  <body><div>
+    <p begin="1s" end="2s" ttm:agent="v1">one</p>
+    <p begin="2s" end="3s" ttm:agent="v1">two</p>
+    <p begin="3s" end="4s" ttm:agent="v2">three &amp; four</p>
+  </div></body>
+</tt>"#;
+    let (origin, translation) = parse_ttml(ttml)?;
+
+    let LyricOwned::LineTimestamp(lines) = origin else {
+        panic!("expected timed lyric, got {origin:?}");
+    };
+    let texts: Vec<_> = lines.iter().map(|line| line.text.as_str()).collect();
+    assert_eq!(texts, ["Alice: one", "two", "Bob: three & four"]);
+    assert!(lines.iter().all(|line| line.words.is_empty()));
+    assert!(translation.is_none());
+    Ok(())
+}
+
+#[test]
+fn time_expressions() {
+    assert_eq!(
+        parse_ttml_time("1:02.345"),
+        Some(Duration::from_millis(62_345))
+    );
+    assert_eq!(
+        parse_ttml_time("62.345s"),
+        Some(Duration::from_millis(62_345))
+    );
+    assert_eq!(parse_ttml_time("500ms"), Some(Duration::from_millis(500)));
+    assert_eq!(
+        parse_ttml_time("01:00:00.5"),
+        Some(Duration::from_millis(3_600_500))
+    );
+    assert_eq!(parse_ttml_time("12.5"), Some(Duration::from_millis(12_500)));
+    assert_eq!(parse_ttml_time("abc"), None);
+    assert_eq!(parse_ttml_time("1:2:3:4"), None);
+}
+
+#[test]
+fn invalid_time() {
+    let ttml = r#"<tt><body><div><p begin="soon">text</p></div></body></tt>"#;
+    assert!(matches!(parse_ttml(ttml), Err(TtmlError::Time(time)) if time == "soon"));
+}
+
+#[test]
+fn export_roundtrip() -> Result<()> {
+    let origin = vec![
+        LyricLineOwned {
+            text: "Hello world".into(),
+            start_time: Duration::from_secs(1),
+            end_time: Some(Duration::from_secs(3)),
+            words: vec![word("Hello ", 1000, 2000), word("world", 2000, 3000)],
+        },
+        LyricLineOwned {
+            text: "<你好>".into(),
+            start_time: Duration::from_secs(4),
+            end_time: None,
+            words: vec![],
+        },
+    ];
+    let translation = vec![LyricLineOwned {
+        text: "你好世界".into(),
+        start_time: Duration::from_secs(1),
+        end_time: Some(Duration::from_secs(3)),
+        words: vec![],
+    }];
+
+    let ttml = make_ttml(&origin, &translation);
+    let (parsed_origin, parsed_translation) = parse_ttml(&ttml)?;
+
+    assert_eq!(parsed_origin, LyricOwned::LineTimestamp(origin));
+    assert_eq!(parsed_translation, LyricOwned::LineTimestamp(translation));
+    Ok(())
+}
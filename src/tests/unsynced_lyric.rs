@@ -0,0 +1,58 @@
+use std::time::Duration;
+
+use crate::lyric_providers::utils::lrc_or_plain;
+use crate::lyric_providers::LyricOwned;
+use crate::sync::lyric::unsynced::{scroll_line_index, unsynced_lines};
+
+#[test]
+fn plain_text_is_kept() {
+    let lyric = lrc_or_plain("[ti:Song]\nfirst line\n\nsecond line\n").into_owned();
+    assert_eq!(
+        lyric,
+        LyricOwned::NoTimestamp("first line\n\nsecond line".into())
+    );
+}
+
+#[test]
+fn timestamped_lyric_wins() {
+    let lyric = lrc_or_plain("some header\n[00:01.00]first line").into_owned();
+    assert!(lyric.is_line_timestamp());
+}
+
+#[test]
+fn metadata_only_is_none() {
+    let lyric = lrc_or_plain("[ti:Song]\n[ar:Someone]\n").into_owned();
+    assert!(lyric.is_none());
+}
+
+#[test]
+fn cached_plain_text_round_trip() {
+    let lyric = LyricOwned::NoTimestamp("first line".into());
+    let cached = serde_json::to_string(&lyric).unwrap();
+    assert_eq!(serde_json::from_str::<LyricOwned>(&cached).unwrap(), lyric);
+}
+
+#[test]
+fn old_cache_without_plain_text() {
+    let lyric: LyricOwned = serde_json::from_str(r#"{"type":"NoTimestamp"}"#).unwrap();
+    assert!(lyric.is_none());
+}
+
+#[test]
+fn skip_empty_lines() {
+    assert_eq!(
+        unsynced_lines("first\n  \nsecond \n"),
+        vec!["first", "second"]
+    );
+}
+
+#[test]
+fn scroll_pace() {
+    let length = Duration::from_secs(100);
+    assert_eq!(scroll_line_index(4, Duration::ZERO, length), 0);
+    assert_eq!(scroll_line_index(4, Duration::from_secs(26), length), 1);
+    assert_eq!(scroll_line_index(4, Duration::from_secs(99), length), 3);
+    // player may report position beyond length
+    assert_eq!(scroll_line_index(4, Duration::from_secs(120), length), 3);
+    assert_eq!(scroll_line_index(0, Duration::from_secs(10), length), 0);
+}
@@ -0,0 +1,129 @@
+use std::time::Duration;
+
+use anyhow::Result;
+
+use crate::lyric_providers::utils::lrc_iter;
+use crate::lyric_providers::{
+    split_extended_timestamps, LyricLine, LyricLineOwned, LyricOwned, LyricWord, LyricWordOwned,
+};
+
+fn owned_words(words: Vec<LyricWord<'_>>) -> Vec<LyricWordOwned> {
+    words.into_iter().map(LyricWord::<'_>::into_owned).collect()
+}
+
+#[test]
+fn no_word_tags() {
+    let words = split_extended_timestamps("[00:12.34]<FM> 纯音乐", Duration::ZERO);
+    assert!(words.is_empty());
+}
+
+#[test]
+fn split_words() {
+    let words = split_extended_timestamps(
+        " <00:01.00>Hi <00:02.5>there<00:03.00> ",
+        Duration::from_secs(1),
+    );
+    assert_eq!(
+        owned_words(words),
+        vec![
+            LyricWordOwned {
+                text: "Hi ".into(),
+                start_time: Duration::from_secs(1),
+                end_time: None,
+            },
+            LyricWordOwned {
+                text: "there".into(),
+                start_time: Duration::from_millis(2500),
+                end_time: Some(Duration::from_secs(3)),
+            },
+        ]
+    );
+}
+
+#[test]
+fn text_before_first_tag() {
+    let words = split_extended_timestamps("I <00:02.00>love", Duration::from_secs(1));
+    assert_eq!(
+        owned_words(words),
+        vec![
+            LyricWordOwned {
+                text: "I ".into(),
+                start_time: Duration::from_secs(1),
+                end_time: None,
+            },
+            LyricWordOwned {
+                text: "love".into(),
+                start_time: Duration::from_secs(2),
+                end_time: None,
+            },
+        ]
+    );
+}
+
+#[test]
+fn lrc_keeps_word_timings() -> Result<()> {
+    let lrc = "[00:01.00]<00:01.00>你<00:01.50>好<00:02.00>";
+    let lyrics = lrc_iter(lrc.lines())?
+        .into_iter()
+        .map(LyricLine::into_owned)
+        .collect::<Vec<_>>();
+
+    let [line] = lyrics.as_slice() else {
+        panic!("expected exactly one line, got {lyrics:?}");
+    };
+    assert_eq!(line.text, "你好");
+    assert_eq!(
+        line.words
+            .iter()
+            .map(|w| w.text.as_str())
+            .collect::<String>(),
+        line.text
+    );
+    assert_eq!(line.words[1].end_time, Some(Duration::from_secs(2)));
+    Ok(())
+}
+
+#[test]
+fn repeated_line_has_no_words() -> Result<()> {
+    let lrc = "[00:01.00][00:31.00]<00:01.00>你<00:01.50>好";
+    let lyrics = lrc_iter(lrc.lines())?;
+
+    assert_eq!(lyrics.len(), 2);
+    assert!(lyrics.iter().all(|line| line.words.is_empty()));
+    Ok(())
+}
+
+#[test]
+fn cache_without_words() -> Result<()> {
+    let cached =
+        r#"{"type":"LineTimestamp","content":[{"text":"Hi","start_time":{"secs":1,"nanos":0}}]}"#;
+    let lyric: LyricOwned = serde_json::from_str(cached)?;
+    assert_eq!(
+        lyric,
+        LyricOwned::LineTimestamp(vec![LyricLineOwned {
+            text: "Hi".into(),
+            start_time: Duration::from_secs(1),
+            ..Default::default()
+        }])
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "export-lyric")]
+fn export_word_timed_line() -> Result<()> {
+    use crate::lyric_providers::utils::{make_lrc_line, make_word_timed_text};
+
+    let lrc = "[00:01.000]<00:01.000>Hi <00:01.500>there<00:02.000>";
+    let line = lrc_iter(lrc.lines())?
+        .into_iter()
+        .map(LyricLine::into_owned)
+        .next()
+        .unwrap();
+
+    assert_eq!(
+        make_lrc_line(make_word_timed_text(&line.words), line.start_time),
+        lrc
+    );
+    Ok(())
+}
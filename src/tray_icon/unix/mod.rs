@@ -194,6 +194,95 @@ impl Tray for TrayIcon {
                         ..Default::default()
                     }
                     .into(),
+                    StandardItem {
+                        label: gettext("Bilingual Lyric"),
+                        activate: Box::new(|_| {
+                            let _ = play_action().send_blocking(PlayAction::ExportBilingualLyric);
+                        }),
+                        ..Default::default()
+                    }
+                    .into(),
+                    StandardItem {
+                        label: gettext("Bilingual Lyric (Same Line)"),
+                        activate: Box::new(|_| {
+                            let _ = play_action()
+                                .send_blocking(PlayAction::ExportBilingualLyricSameLine);
+                        }),
+                        ..Default::default()
+                    }
+                    .into(),
+                    StandardItem {
+                        label: gettext("Embed into File"),
+                        activate: Box::new(|_| {
+                            let _ = play_action().send_blocking(PlayAction::EmbedLyric);
+                        }),
+                        ..Default::default()
+                    }
+                    .into(),
+                ],
+                ..Default::default()
+            }
+            .into(),
+            SubMenu {
+                label: gettext("Lyric Offset"),
+                icon_name: "preferences-system-time".into(),
+                submenu: vec![
+                    StandardItem {
+                        label: gettext("Delay 100ms"),
+                        activate: Box::new(|_| {
+                            let _ = play_action().send_blocking(PlayAction::IncreaseLyricOffset);
+                        }),
+                        ..Default::default()
+                    }
+                    .into(),
+                    StandardItem {
+                        label: gettext("Advance 100ms"),
+                        activate: Box::new(|_| {
+                            let _ = play_action().send_blocking(PlayAction::DecreaseLyricOffset);
+                        }),
+                        ..Default::default()
+                    }
+                    .into(),
+                    StandardItem {
+                        label: gettext("Reset Offset"),
+                        activate: Box::new(|_| {
+                            let _ = play_action().send_blocking(PlayAction::ResetLyricOffset);
+                        }),
+                        ..Default::default()
+                    }
+                    .into(),
+                    StandardItem {
+                        label: gettext("Current Line Starts Now"),
+                        activate: Box::new(|_| {
+                            let _ = play_action().send_blocking(PlayAction::SyncNowCurrentLine);
+                        }),
+                        ..Default::default()
+                    }
+                    .into(),
+                    StandardItem {
+                        label: gettext("Next Line Starts Now"),
+                        activate: Box::new(|_| {
+                            let _ = play_action().send_blocking(PlayAction::SyncNowNextLine);
+                        }),
+                        ..Default::default()
+                    }
+                    .into(),
+                    StandardItem {
+                        label: gettext("Stretch by Last Two Synced Lines"),
+                        activate: Box::new(|_| {
+                            let _ = play_action().send_blocking(PlayAction::StretchLyricBySync);
+                        }),
+                        ..Default::default()
+                    }
+                    .into(),
+                    StandardItem {
+                        label: gettext("Reset Stretch"),
+                        activate: Box::new(|_| {
+                            let _ = play_action().send_blocking(PlayAction::ResetLyricStretch);
+                        }),
+                        ..Default::default()
+                    }
+                    .into(),
                 ],
                 ..Default::default()
             }
@@ -207,6 +296,24 @@ impl Tray for TrayIcon {
                 ..Default::default()
             }
             .into(),
+            StandardItem {
+                label: gettext("Edit Lyric"),
+                icon_name: "document-edit".into(),
+                activate: Box::new(|_| {
+                    let _ = play_action().send_blocking(PlayAction::EditLyric);
+                }),
+                ..Default::default()
+            }
+            .into(),
+            StandardItem {
+                label: gettext("Tap to Sync Lyric"),
+                icon_name: "input-keyboard".into(),
+                activate: Box::new(|_| {
+                    let _ = play_action().send_blocking(PlayAction::TapSyncLyric);
+                }),
+                ..Default::default()
+            }
+            .into(),
             StandardItem {
                 label: gettext("Refetch Lyric"),
                 icon_name: "folder-download".into(),
@@ -18,9 +18,36 @@ pub fn start_tray_service() -> Result<()> {
     tray.add_menu_item(&gettext("Search lyric"), || {
         let _ = play_action().send_blocking(PlayAction::SearchLyric);
     })?;
+    tray.add_menu_item(&gettext("Edit lyric"), || {
+        let _ = play_action().send_blocking(PlayAction::EditLyric);
+    })?;
+    tray.add_menu_item(&gettext("Tap to sync lyric"), || {
+        let _ = play_action().send_blocking(PlayAction::TapSyncLyric);
+    })?;
     tray.add_menu_item(&gettext("Refetch lyric"), || {
         let _ = play_action().send_blocking(PlayAction::RefetchLyric);
     })?;
+    tray.add_menu_item(&gettext("Delay lyric 100ms"), || {
+        let _ = play_action().send_blocking(PlayAction::IncreaseLyricOffset);
+    })?;
+    tray.add_menu_item(&gettext("Advance lyric 100ms"), || {
+        let _ = play_action().send_blocking(PlayAction::DecreaseLyricOffset);
+    })?;
+    tray.add_menu_item(&gettext("Reset lyric offset"), || {
+        let _ = play_action().send_blocking(PlayAction::ResetLyricOffset);
+    })?;
+    tray.add_menu_item(&gettext("Current lyric line starts now"), || {
+        let _ = play_action().send_blocking(PlayAction::SyncNowCurrentLine);
+    })?;
+    tray.add_menu_item(&gettext("Next lyric line starts now"), || {
+        let _ = play_action().send_blocking(PlayAction::SyncNowNextLine);
+    })?;
+    tray.add_menu_item(&gettext("Stretch lyric by last two synced lines"), || {
+        let _ = play_action().send_blocking(PlayAction::StretchLyricBySync);
+    })?;
+    tray.add_menu_item(&gettext("Reset lyric stretch"), || {
+        let _ = play_action().send_blocking(PlayAction::ResetLyricStretch);
+    })?;
     tray.add_menu_item(&gettext("Toggle Decoration"), || {
         let _ = ui_action().send_blocking(UIAction::SwitchDecoration);
     })?;
@@ -1,10 +1,11 @@
 use anyhow::Result;
 use gtk::glib::subclass::types::ObjectSubclassIsExt;
+use gtk::prelude::*;
 use std::path::PathBuf;
 use std::sync::OnceLock;
 use std::time::Duration;
 
-use crate::app::{get_label, Window};
+use crate::app::{get_label, lyric_labels, Window};
 use crate::config::Config;
 use crate::DEFAULT_TEXT;
 
@@ -24,8 +25,26 @@ pub fn reset_lyric_labels(window: &Window, tip: Option<&str>) {
         }
     });
 
+    for position in ["above", "romanization", "below"] {
+        let label = get_label(window, position);
+        // karaoke mode renders labels with markup
+        label.set_use_markup(false);
+        label.remove_css_class("karaoke");
+        label.remove_css_class("ended");
+    }
+
     get_label(window, "above").set_label(tip);
+    get_label(window, "romanization").set_label("");
     get_label(window, "below").set_label("");
+    clear_context_labels(window);
+    window.imp().current_line.set(None);
+}
+
+pub fn clear_context_labels(window: &Window) {
+    let labels = lyric_labels(window);
+    for label in labels.previous.iter().chain(&labels.next) {
+        label.set_label("");
+    }
 }
 
 pub fn parse_time(time: &str) -> Result<Duration, ParseError> {